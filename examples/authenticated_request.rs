@@ -16,6 +16,7 @@ impl ServiceStackRequest for SecureRequest {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct SecureResponse {
     data: String,
 }
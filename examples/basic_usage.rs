@@ -2,14 +2,6 @@ use serde::{Deserialize, Serialize};
 use servicestack::{JsonServiceClient, ServiceStackRequest, ServiceStackResponse};
 
 /// Example request DTO
-//! Basic usage example for the ServiceStack client library
-//!
-//! This example demonstrates how to use the ServiceStack client
-//! to make HTTP requests to a ServiceStack service.
-
-use servicestack::{ServiceStackClient, Result};
-use serde::{Deserialize, Serialize};
-
 #[derive(Serialize, Debug)]
 struct HelloRequest {
     name: String,
@@ -26,6 +18,7 @@ impl ServiceStackRequest for HelloRequest {
 
 /// Example response DTO
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct HelloResponse {
     result: String,
 }
@@ -54,29 +47,5 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         }
     }
 
-#[tokio::main]
-async fn main() -> Result<()> {
-    // Create a new ServiceStack client
-    let client = ServiceStackClient::new("https://test.servicestack.net");
-
-    // Example 1: Simple GET request
-    println!("Example 1: GET request");
-    match client.get::<serde_json::Value>("/hello/World").await {
-        Ok(response) => println!("Response: {:?}", response),
-        Err(e) => eprintln!("Error: {}", e),
-    }
-
-    // Example 2: POST request with JSON body
-    println!("\nExample 2: POST request");
-    let request = HelloRequest {
-        name: "Rust".to_string(),
-    };
-    
-    match client.post::<_, HelloResponse>("/hello", &request).await {
-        Ok(response) => println!("Response: {:?}", response),
-        Err(e) => eprintln!("Error: {}", e),
-    }
-
-    println!("\nExamples completed!");
     Ok(())
 }
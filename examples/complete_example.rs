@@ -17,23 +17,25 @@ impl ServiceStackRequest for HelloRequest {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct HelloResponse {
     result: String,
 }
 
 impl ServiceStackResponse for HelloResponse {}
 
-// Example 2: GET request with query parameters
+// Example 2: GET request with a route template and query parameters
 #[derive(Serialize, Debug)]
 struct GetUserRequest {
     id: u32,
+    include_deleted: bool,
 }
 
 impl ServiceStackRequest for GetUserRequest {
     type Response = UserResponse;
 
-    fn path(&self) -> String {
-        format!("/users/{}", self.id)
+    fn routes() -> &'static [&'static str] {
+        &["/users/{Id}"]
     }
 
     fn method(&self) -> HttpMethod {
@@ -42,6 +44,7 @@ impl ServiceStackRequest for GetUserRequest {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct UserResponse {
     id: u32,
     name: String,
@@ -61,8 +64,8 @@ struct UpdateUserRequest {
 impl ServiceStackRequest for UpdateUserRequest {
     type Response = UpdateResponse;
 
-    fn path(&self) -> String {
-        format!("/users/{}", self.id)
+    fn routes() -> &'static [&'static str] {
+        &["/users/{Id}"]
     }
 
     fn method(&self) -> HttpMethod {
@@ -71,6 +74,7 @@ impl ServiceStackRequest for UpdateUserRequest {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct UpdateResponse {
     success: bool,
     message: String,
@@ -87,8 +91,8 @@ struct DeleteUserRequest {
 impl ServiceStackRequest for DeleteUserRequest {
     type Response = DeleteResponse;
 
-    fn path(&self) -> String {
-        format!("/users/{}", self.id)
+    fn routes() -> &'static [&'static str] {
+        &["/users/{Id}"]
     }
 
     fn method(&self) -> HttpMethod {
@@ -97,6 +101,7 @@ impl ServiceStackRequest for DeleteUserRequest {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct DeleteResponse {
     success: bool,
 }
@@ -128,8 +133,12 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
 
     println!("2. GET request:");
     println!("   This would make a GET request to retrieve a user");
-    let get_request = GetUserRequest { id: 1 };
-    println!("   Request path: {}", get_request.path());
+    let get_request = GetUserRequest {
+        id: 1,
+        include_deleted: false,
+    };
+    println!("   Route: {:?}", GetUserRequest::routes());
+    println!("   Request: {:?}", get_request);
     println!("   (Skipping actual request to avoid errors on demo endpoint)\n");
 
     println!("3. PUT request with authentication:");
@@ -141,12 +150,13 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         name: "Updated Name".to_string(),
         email: "updated@example.com".to_string(),
     };
-    println!("   Request path: {}", update_request.path());
+    println!("   Route: {:?}", UpdateUserRequest::routes());
+    println!("   Request: {:?}", update_request);
     println!("   (Skipping actual request to avoid errors on demo endpoint)\n");
 
     println!("4. DELETE request:");
     let delete_request = DeleteUserRequest { id: 1 };
-    println!("   Request path: {}", delete_request.path());
+    println!("   Route: {:?}", DeleteUserRequest::routes());
     println!("   Method: {:?}", delete_request.method());
     println!("   (Skipping actual request to avoid errors on demo endpoint)\n");
 
@@ -22,6 +22,7 @@ impl ServiceStackRequest for UpdateRequest {
 }
 
 #[derive(Deserialize, Debug)]
+#[allow(dead_code)]
 struct UpdateResponse {
     success: bool,
     message: String,
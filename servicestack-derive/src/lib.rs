@@ -0,0 +1,196 @@
+//! # ServiceStack Derive Macros
+//!
+//! Derive macros for the `ServiceStackRequest` and `ServiceStackResponse` traits of the
+//! [`servicestack`](https://docs.rs/servicestack) crate. Enable them with the `derive`
+//! feature of `servicestack` rather than depending on this crate directly.
+//!
+//! ## Example
+//!
+//! ```
+//! use serde::{Deserialize, Serialize};
+//! use servicestack::{ServiceStackRequest, ServiceStackResponse};
+//!
+//! #[derive(Serialize, ServiceStackRequest)]
+//! #[servicestack(response = UserResponse, route = "/users/{Id}", method = "GET")]
+//! struct GetUser {
+//!     id: u32,
+//! }
+//!
+//! #[derive(Deserialize, ServiceStackResponse)]
+//! struct UserResponse {
+//!     name: String,
+//! }
+//! ```
+
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::{parse_macro_input, DeriveInput, LitBool, LitStr, Type};
+
+/// Derives `servicestack::ServiceStackRequest`
+///
+/// Configured with a `#[servicestack(...)]` attribute:
+///
+/// * `response = Type` - the response DTO type (required)
+/// * `route = "/path/{Field}"` - a route template, may be repeated
+/// * `method = "GET"` - the HTTP method, defaults to POST; names other than the standard
+///   methods are sent as custom verbs
+/// * `operation = "Name"` - the operation name, defaults to the type name
+/// * `retryable = true` - whether failed requests may be retried, defaults to
+///   retrying only idempotent methods
+#[proc_macro_derive(ServiceStackRequest, attributes(servicestack))]
+pub fn derive_request(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    expand_request(&input)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+/// Derives `servicestack::ServiceStackResponse`
+#[proc_macro_derive(ServiceStackResponse)]
+pub fn derive_response(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    quote! {
+        impl #impl_generics ::servicestack::ServiceStackResponse for #name #ty_generics #where_clause {}
+    }
+    .into()
+}
+
+/// Options collected from the `#[servicestack(...)]` attributes
+#[derive(Default)]
+struct RequestOptions {
+    response: Option<Type>,
+    routes: Vec<LitStr>,
+    method: Option<TokenStream2>,
+    operation: Option<LitStr>,
+    retryable: Option<LitBool>,
+}
+
+fn expand_request(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let options = parse_options(input)?;
+    let name = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let response = options.response.ok_or_else(|| {
+        syn::Error::new_spanned(name, "missing `#[servicestack(response = Type)]` attribute")
+    })?;
+
+    let routes = &options.routes;
+    let routes = (!routes.is_empty()).then(|| {
+        quote! {
+            fn routes() -> &'static [&'static str] {
+                &[#(#routes),*]
+            }
+        }
+    });
+
+    let method = options.method.map(|method| {
+        quote! {
+            fn method(&self) -> ::servicestack::HttpMethod {
+                #method
+            }
+        }
+    });
+
+    let operation = options.operation.map(|operation| {
+        quote! {
+            fn operation_name() -> &'static str {
+                #operation
+            }
+        }
+    });
+
+    let retryable = options.retryable.map(|retryable| {
+        quote! {
+            fn retryable(&self) -> ::std::option::Option<bool> {
+                ::std::option::Option::Some(#retryable)
+            }
+        }
+    });
+
+    Ok(quote! {
+        impl #impl_generics ::servicestack::ServiceStackRequest for #name #ty_generics #where_clause {
+            type Response = #response;
+
+            #routes
+            #method
+            #operation
+            #retryable
+        }
+    })
+}
+
+fn parse_options(input: &DeriveInput) -> syn::Result<RequestOptions> {
+    let mut options = RequestOptions::default();
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("servicestack"))
+    {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("response") {
+                options.response = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("route") {
+                let route: LitStr = meta.value()?.parse()?;
+                if !route.value().starts_with('/') {
+                    return Err(syn::Error::new_spanned(route, "route must start with `/`"));
+                }
+                options.routes.push(route);
+            } else if meta.path.is_ident("method") {
+                let method: LitStr = meta.value()?.parse()?;
+                options.method = Some(http_method(&method)?);
+            } else if meta.path.is_ident("operation") {
+                options.operation = Some(meta.value()?.parse()?);
+            } else if meta.path.is_ident("retryable") {
+                options.retryable = Some(meta.value()?.parse()?);
+            } else {
+                return Err(meta.error(
+                    "unknown servicestack attribute, expected `response`, `route`, `method`, `operation` or `retryable`",
+                ));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(options)
+}
+
+/// Maps an HTTP method name to the matching `servicestack::HttpMethod` variant
+///
+/// Names other than the standard methods become `HttpMethod::Custom`.
+fn http_method(method: &LitStr) -> syn::Result<TokenStream2> {
+    let name = method.value();
+    let variant = match name.to_uppercase().as_str() {
+        "GET" => quote!(Get),
+        "POST" => quote!(Post),
+        "PUT" => quote!(Put),
+        "DELETE" => quote!(Delete),
+        "PATCH" => quote!(Patch),
+        "HEAD" => quote!(Head),
+        "OPTIONS" => quote!(Options),
+        _ if is_token(&name) => {
+            return Ok(quote! {
+                ::servicestack::HttpMethod::Custom(::std::string::String::from(#method))
+            })
+        }
+        _ => {
+            return Err(syn::Error::new_spanned(
+                method,
+                "invalid HTTP method, expected a method name such as GET, POST or PROPFIND",
+            ))
+        }
+    };
+    Ok(quote!(::servicestack::HttpMethod::#variant))
+}
+
+/// Returns whether the name is a valid HTTP method token (RFC 9110)
+fn is_token(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .bytes()
+            .all(|b| b.is_ascii_alphanumeric() || b"!#$%&'*+-.^_`|~".contains(&b))
+}
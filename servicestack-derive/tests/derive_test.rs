@@ -0,0 +1,91 @@
+use serde::{Deserialize, Serialize};
+use servicestack::{HttpMethod, ServiceStackRequest, ServiceStackResponse};
+
+#[derive(Serialize, ServiceStackRequest)]
+#[servicestack(response = UserResponse, route = "/users", route = "/users/{Id}", method = "GET")]
+struct GetUser {
+    id: Option<u32>,
+}
+
+#[derive(Serialize, ServiceStackRequest)]
+#[servicestack(response = UserResponse, operation = "CreateUser", retryable = true)]
+struct NewUser {
+    name: String,
+}
+
+#[derive(Serialize, ServiceStackRequest)]
+#[servicestack(response = UserResponse)]
+#[servicestack(method = "put")]
+struct Wrapped<T: Serialize> {
+    value: T,
+}
+
+#[derive(Serialize, ServiceStackRequest)]
+#[servicestack(response = UserResponse, route = "/users/{Id}", method = "PROPFIND")]
+struct FindUser {
+    id: u32,
+}
+
+#[derive(Serialize, ServiceStackRequest)]
+#[servicestack(response = (), method = "head")]
+struct UserExists {
+    id: u32,
+}
+
+#[derive(Deserialize, Debug, PartialEq, ServiceStackResponse)]
+struct UserResponse {
+    name: String,
+}
+
+fn assert_response<T: ServiceStackResponse>(json: &str) -> T {
+    serde_json::from_str(json).unwrap()
+}
+
+#[test]
+fn test_derive_routes_and_method() {
+    let request = GetUser { id: Some(1) };
+    assert_eq!(GetUser::routes(), &["/users", "/users/{Id}"]);
+    assert_eq!(request.method(), HttpMethod::Get);
+    assert_eq!(request.path(), "");
+    assert_eq!(GetUser::operation_name(), "GetUser");
+    assert_eq!(request.retryable(), None);
+}
+
+#[test]
+fn test_derive_operation_name_and_defaults() {
+    let request = NewUser {
+        name: "Jane".to_string(),
+    };
+    assert_eq!(NewUser::operation_name(), "CreateUser");
+    assert!(NewUser::routes().is_empty());
+    assert_eq!(request.method(), HttpMethod::Post);
+    assert_eq!(request.retryable(), Some(true));
+}
+
+#[test]
+fn test_derive_generics_and_multiple_attributes() {
+    let request = Wrapped { value: 5 };
+    assert_eq!(request.method(), HttpMethod::Put);
+    assert_eq!(Wrapped::<u32>::operation_name(), "Wrapped");
+    assert_eq!(request.value, 5);
+}
+
+#[test]
+fn test_derive_custom_and_head_methods() {
+    assert_eq!(
+        FindUser { id: 1 }.method(),
+        HttpMethod::Custom("PROPFIND".to_string())
+    );
+    assert_eq!(UserExists { id: 1 }.method(), HttpMethod::Head);
+}
+
+#[test]
+fn test_derive_response() {
+    let response: UserResponse = assert_response(r#"{"name":"Jane"}"#);
+    assert_eq!(
+        response,
+        UserResponse {
+            name: "Jane".to_string()
+        }
+    );
+}
@@ -0,0 +1,128 @@
+//! Responses with their HTTP metadata
+//!
+//! [`JsonServiceClient::send_with_metadata`](crate::JsonServiceClient::send_with_metadata)
+//! returns an [`ApiResponse`] holding the deserialized DTO together with the status code,
+//! headers, final URL and elapsed time, e.g. to read `ETag`, `X-Total-Count` or rate-limit
+//! headers without a response filter.
+
+use reqwest::header::{HeaderMap, ETAG};
+use reqwest::{StatusCode, Url};
+use std::time::Duration;
+
+/// A deserialized response DTO with the response's status, headers, URL and timing
+#[derive(Debug, Clone)]
+pub struct ApiResponse<T> {
+    body: T,
+    status: StatusCode,
+    headers: HeaderMap,
+    url: Url,
+    elapsed: Duration,
+}
+
+impl<T> ApiResponse<T> {
+    pub(crate) fn new(
+        body: T,
+        status: StatusCode,
+        headers: HeaderMap,
+        url: Url,
+        elapsed: Duration,
+    ) -> Self {
+        Self {
+            body,
+            status,
+            headers,
+            url,
+            elapsed,
+        }
+    }
+
+    /// Returns the response DTO
+    pub fn body(&self) -> &T {
+        &self.body
+    }
+
+    /// Consumes the envelope, returning the response DTO
+    pub fn into_body(self) -> T {
+        self.body
+    }
+
+    /// Returns the HTTP status code
+    pub fn status(&self) -> u16 {
+        self.status.as_u16()
+    }
+
+    /// Returns the response headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the value of a header, if present and valid UTF-8
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name)?.to_str().ok()
+    }
+
+    /// Returns all values of a header that may be repeated, such as `Set-Cookie`
+    pub fn header_values(&self, name: &str) -> Vec<&str> {
+        self.headers
+            .get_all(name)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .collect()
+    }
+
+    /// Returns the `ETag` header
+    pub fn etag(&self) -> Option<&str> {
+        self.header(ETAG.as_str())
+    }
+
+    /// Returns the final URL of the response, after any redirects
+    pub fn url(&self) -> &Url {
+        &self.url
+    }
+
+    /// Returns the time from sending the request until the body was read
+    pub fn elapsed(&self) -> Duration {
+        self.elapsed
+    }
+
+    /// Maps the response DTO, keeping the metadata
+    pub fn map<U>(self, f: impl FnOnce(T) -> U) -> ApiResponse<U> {
+        ApiResponse {
+            body: f(self.body),
+            status: self.status,
+            headers: self.headers,
+            url: self.url,
+            elapsed: self.elapsed,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::{HeaderValue, SET_COOKIE};
+
+    #[test]
+    fn test_headers_and_map() {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_static("\"v1\""));
+        headers.append(SET_COOKIE, HeaderValue::from_static("a=1"));
+        headers.append(SET_COOKIE, HeaderValue::from_static("b=2"));
+        let response = ApiResponse::new(
+            2,
+            StatusCode::CREATED,
+            headers,
+            Url::parse("https://api.example.com/users").unwrap(),
+            Duration::from_millis(5),
+        );
+
+        assert_eq!(response.etag(), Some("\"v1\""));
+        assert_eq!(response.header_values("set-cookie"), vec!["a=1", "b=2"]);
+        assert_eq!(response.header("X-Missing"), None);
+
+        let response = response.map(|id| id * 10);
+        assert_eq!(response.body(), &20);
+        assert_eq!(response.status(), 201);
+        assert_eq!(response.elapsed(), Duration::from_millis(5));
+    }
+}
@@ -0,0 +1,167 @@
+//! Non-throwing results like the `api()` method of ServiceStack's clients
+//!
+//! [`JsonServiceClient::api`](crate::JsonServiceClient::api) returns an [`ApiResult`] holding
+//! either the response DTO or a [`ResponseStatus`] describing the error, which suits UI code
+//! that displays error and validation messages rather than propagating errors. Failures that
+//! never reached the service, such as timeouts, are turned into a `ResponseStatus` too.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use servicestack::{JsonServiceClient, ServiceStackRequest};
+//! # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, request: T) {
+//! let result = client.api(request).await;
+//! if result.succeeded() {
+//!     let response = result.response();
+//! } else if let Some(message) = result.field_error_message("Email") {
+//!     println!("Email: {}", message);
+//! } else {
+//!     println!("{}", result.summary_message().unwrap_or("Unknown error"));
+//! }
+//! # }
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use crate::response_status::{ResponseError, ResponseStatus};
+
+/// Either a response DTO or the [`ResponseStatus`] of the error that prevented it
+#[derive(Debug, Clone)]
+pub struct ApiResult<T> {
+    response: Option<T>,
+    error: Option<ResponseStatus>,
+    status_code: Option<u16>,
+}
+
+impl<T> ApiResult<T> {
+    /// Creates a successful result
+    pub fn success(response: T) -> Self {
+        Self {
+            response: Some(response),
+            error: None,
+            status_code: None,
+        }
+    }
+
+    /// Creates a failed result from a `ResponseStatus`
+    pub fn failure(error: ResponseStatus) -> Self {
+        Self {
+            response: None,
+            error: Some(error),
+            status_code: None,
+        }
+    }
+
+    /// Returns whether the request succeeded
+    pub fn succeeded(&self) -> bool {
+        self.error.is_none()
+    }
+
+    /// Returns whether the request failed
+    pub fn failed(&self) -> bool {
+        self.error.is_some()
+    }
+
+    /// Returns the response DTO if the request succeeded
+    pub fn response(&self) -> Option<&T> {
+        self.response.as_ref()
+    }
+
+    /// Consumes the result, returning the response DTO if the request succeeded
+    pub fn into_response(self) -> Option<T> {
+        self.response
+    }
+
+    /// Returns the error if the request failed
+    pub fn error(&self) -> Option<&ResponseStatus> {
+        self.error.as_ref()
+    }
+
+    /// Returns the HTTP status code of an error response, if the service responded
+    pub fn status_code(&self) -> Option<u16> {
+        self.status_code
+    }
+
+    /// Returns the error code if the request failed
+    pub fn error_code(&self) -> Option<&str> {
+        self.error.as_ref()?.error_code.as_deref()
+    }
+
+    /// Returns the message summarizing the error, if the request failed
+    pub fn summary_message(&self) -> Option<&str> {
+        self.error.as_ref()?.summary_message()
+    }
+
+    /// Returns the first validation error for a field, matching the name case-insensitively
+    pub fn field_error(&self, field_name: &str) -> Option<&ResponseError> {
+        self.error.as_ref()?.field_error(field_name)
+    }
+
+    /// Returns the first validation error message for a field, if any
+    pub fn field_error_message(&self, field_name: &str) -> Option<&str> {
+        self.field_error(field_name)?.message.as_deref()
+    }
+}
+
+impl<T> From<Result<T>> for ApiResult<T> {
+    fn from(result: Result<T>) -> Self {
+        match result {
+            Ok(response) => ApiResult::success(response),
+            Err(error) => ApiResult {
+                response: None,
+                status_code: error.status_code(),
+                error: Some(error.to_response_status()),
+            },
+        }
+    }
+}
+
+impl<T> From<ServiceStackError> for ApiResult<T> {
+    fn from(error: ServiceStackError) -> Self {
+        Err(error).into()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_success() {
+        let result: ApiResult<u32> = Ok(5).into();
+        assert!(result.succeeded());
+        assert_eq!(result.response(), Some(&5));
+        assert!(result.error().is_none());
+        assert!(result.summary_message().is_none());
+        assert_eq!(result.into_response(), Some(5));
+    }
+
+    #[test]
+    fn test_validation_failure() {
+        let body = r#"{"responseStatus":{"errorCode":"NotEmpty","message":"Email is required",
+            "errors":[{"errorCode":"NotEmpty","fieldName":"Email","message":"Email is required"}]}}"#;
+        let result: ApiResult<u32> =
+            ServiceStackError::from_response_body(400, body.to_string()).into();
+        assert!(result.failed());
+        assert_eq!(result.status_code(), Some(400));
+        assert_eq!(result.error_code(), Some("NotEmpty"));
+        assert_eq!(
+            result.field_error_message("email"),
+            Some("Email is required")
+        );
+        assert_eq!(result.summary_message(), Some("Email is required"));
+        assert!(result.response().is_none());
+    }
+
+    #[test]
+    fn test_synthetic_response_status() {
+        let result: ApiResult<u32> =
+            ServiceStackError::from_response_body(503, "Service Unavailable".to_string()).into();
+        assert_eq!(result.error_code(), Some("ServiceUnavailable"));
+        assert_eq!(result.summary_message(), Some("Service Unavailable"));
+
+        let result: ApiResult<u32> = ServiceStackError::message("boom").into();
+        assert_eq!(result.status_code(), None);
+        assert_eq!(result.error_code(), Some("Exception"));
+        assert_eq!(result.summary_message(), Some("Error: boom"));
+    }
+}
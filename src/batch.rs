@@ -0,0 +1,43 @@
+//! Support for ServiceStack's auto-batched requests
+
+use crate::error::{Result, ServiceStackError};
+use crate::middleware::{Middleware, Next};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+/// Header with the number of requests that completed before a batch failed
+pub(crate) const AUTO_BATCH_COMPLETED: &str = "X-AutoBatch-Completed";
+
+/// Records the `X-AutoBatch-Completed` header of a batch response
+#[derive(Clone, Default)]
+pub(crate) struct Completed {
+    count: Arc<Mutex<Option<usize>>>,
+}
+
+impl Completed {
+    /// Wraps a service error in `BatchError` with the index of the item that failed
+    pub(crate) fn batch_error(&self, error: ServiceStackError) -> ServiceStackError {
+        if !matches!(error, ServiceStackError::ApiError { .. }) {
+            return error;
+        }
+        let index = *self.count.lock().unwrap_or_else(|e| e.into_inner());
+        ServiceStackError::BatchError {
+            index,
+            source: Box::new(error),
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for Completed {
+    async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response> {
+        let response = next.run(request).await?;
+        let count = response
+            .headers()
+            .get(AUTO_BATCH_COMPLETED)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok());
+        *self.count.lock().unwrap_or_else(|e| e.into_inner()) = count;
+        Ok(response)
+    }
+}
@@ -0,0 +1,261 @@
+//! Blocking JSON service client
+//!
+//! [`blocking::JsonServiceClient`](JsonServiceClient) has the same typed API as the async
+//! [`JsonServiceClient`](crate::JsonServiceClient) for programs without an async
+//! runtime, such as CLI tools and build scripts. It is built on `reqwest::blocking` and
+//! needs the `blocking` feature.
+//!
+//! Requests are resolved the same way, with route templates, pre-defined routes and
+//! query strings for GET and DELETE requests, and error responses become
+//! [`ServiceStackError::ApiError`]. The middleware, retry, circuit breaker and caching
+//! features of the async client are not available.
+//!
+//! # Example
+//!
+//! ```no_run
+//! use servicestack::blocking::JsonServiceClient;
+//! use servicestack::{ServiceStackRequest, ServiceStackResponse};
+//! use serde::{Deserialize, Serialize};
+//!
+//! #[derive(Serialize)]
+//! struct Hello {
+//!     name: String,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct HelloResponse {
+//!     result: String,
+//! }
+//!
+//! impl ServiceStackResponse for HelloResponse {}
+//!
+//! impl ServiceStackRequest for Hello {
+//!     type Response = HelloResponse;
+//! }
+//!
+//! fn main() -> servicestack::Result<()> {
+//!     let client = JsonServiceClient::new("https://api.example.com");
+//!     let response = client.post(Hello { name: "World".to_string() })?;
+//!     println!("{}", response.result);
+//!     Ok(())
+//! }
+//! ```
+
+use crate::client::PredefinedRoutes;
+use crate::error::{Result, ServiceStackError};
+use crate::response;
+use crate::route;
+use crate::traits::{HttpMethod, ServiceStackRequest, ServiceStackResponse};
+use reqwest::blocking::{Client, RequestBuilder};
+use serde::{de::DeserializeOwned, Serialize};
+use std::fmt;
+use std::time::Duration;
+
+/// Blocking JSON client for making typed API requests to ServiceStack services
+///
+/// Must not be created or used from within an async runtime; use the async
+/// [`JsonServiceClient`](crate::JsonServiceClient) there instead.
+///
+/// # Example
+///
+/// ```no_run
+/// use servicestack::blocking::JsonServiceClient;
+///
+/// let client = JsonServiceClient::new("https://api.example.com");
+/// ```
+#[derive(Clone)]
+pub struct JsonServiceClient {
+    base_url: String,
+    http_client: Client,
+    bearer_token: Option<String>,
+    predefined_routes: PredefinedRoutes,
+}
+
+impl fmt::Debug for JsonServiceClient {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("JsonServiceClient")
+            .field("base_url", &self.base_url)
+            .field("http_client", &self.http_client)
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("predefined_routes", &self.predefined_routes)
+            .finish()
+    }
+}
+
+impl JsonServiceClient {
+    /// Creates a new blocking client with the specified base URL and a 30 second timeout
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the ServiceStack API (e.g., `https://api.example.com`)
+    pub fn new(base_url: impl Into<String>) -> Self {
+        let http_client = Client::builder()
+            .timeout(Duration::from_secs(30))
+            .build()
+            .expect("Failed to create HTTP client");
+        Self::with_client(base_url, http_client)
+    }
+
+    /// Creates a new blocking client with a custom `reqwest::blocking::Client`
+    ///
+    /// # Arguments
+    ///
+    /// * `base_url` - The base URL of the ServiceStack API
+    /// * `http_client` - A configured reqwest blocking Client
+    pub fn with_client(base_url: impl Into<String>, http_client: Client) -> Self {
+        Self {
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            http_client,
+            bearer_token: None,
+            predefined_routes: PredefinedRoutes::default(),
+        }
+    }
+
+    /// Sets the Bearer token for authentication
+    pub fn set_bearer_token(&mut self, token: impl Into<String>) {
+        self.bearer_token = Some(token.into());
+    }
+
+    /// Clears the Bearer token
+    pub fn clear_bearer_token(&mut self) {
+        self.bearer_token = None;
+    }
+
+    /// Sets the pre-defined routes used for requests without a path or route templates
+    ///
+    /// Defaults to [`PredefinedRoutes::Api`] (`/api/{Operation}`).
+    pub fn set_predefined_routes(&mut self, routes: PredefinedRoutes) {
+        self.predefined_routes = routes;
+    }
+
+    /// Returns the pre-defined routes used for requests without a path or route templates
+    pub fn predefined_routes(&self) -> &PredefinedRoutes {
+        &self.predefined_routes
+    }
+
+    /// Makes a GET request to the API, returning the response DTO on success
+    pub fn get<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
+        self.send_request(request, HttpMethod::Get)
+    }
+
+    /// Makes a POST request to the API, returning the response DTO on success
+    pub fn post<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
+        self.send_request(request, HttpMethod::Post)
+    }
+
+    /// Makes a PUT request to the API, returning the response DTO on success
+    pub fn put<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
+        self.send_request(request, HttpMethod::Put)
+    }
+
+    /// Makes a DELETE request to the API, returning the response DTO on success
+    pub fn delete<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
+        self.send_request(request, HttpMethod::Delete)
+    }
+
+    /// Makes a PATCH request to the API, returning the response DTO on success
+    pub fn patch<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
+        self.send_request(request, HttpMethod::Patch)
+    }
+
+    /// Makes a request using the method specified in the request DTO
+    pub fn send<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
+        let method = request.method();
+        self.send_request(request, method)
+    }
+
+    /// Makes a raw API request with custom serialization
+    ///
+    /// # Arguments
+    ///
+    /// * `method` - The HTTP method (GET, POST, etc.)
+    /// * `path` - The API endpoint path
+    /// * `body` - Optional request body (serialized to the query string for GET and
+    ///   DELETE requests, otherwise to a JSON body)
+    pub fn request<TRequest, TResponse>(
+        &self,
+        method: &str,
+        path: &str,
+        body: Option<&TRequest>,
+    ) -> Result<TResponse>
+    where
+        TRequest: Serialize,
+        TResponse: DeserializeOwned + 'static,
+    {
+        let method: HttpMethod = method.parse()?;
+        let (url, body) = route::request_url(&self.base_url, path, &method, body)?;
+
+        let request_builder = self
+            .http_client
+            .request(reqwest::Method::try_from(&method)?, &url);
+        let request_builder = match body {
+            Some(body) => request_builder.json(body),
+            None => request_builder,
+        };
+
+        let response = self.execute(request_builder)?;
+        if response::is_void::<TResponse>() {
+            return response::void();
+        }
+        response::from_body(&response.bytes()?)
+    }
+
+    /// Returns the base URL of the client
+    pub fn base_url(&self) -> &str {
+        &self.base_url
+    }
+
+    /// Returns a reference to the underlying HTTP client
+    pub fn http_client(&self) -> &Client {
+        &self.http_client
+    }
+
+    /// Sends a request DTO and deserializes the response DTO
+    fn send_request<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        method: HttpMethod,
+    ) -> Result<T::Response> {
+        let (url, body) =
+            route::resolve_url(&self.base_url, &request, &method, &self.predefined_routes)?;
+
+        let request_builder = self
+            .http_client
+            .request(reqwest::Method::try_from(&method)?, &url);
+        let request_builder = match body {
+            Some(body) => request_builder.json(&body),
+            None => request_builder,
+        };
+
+        let response = self.execute(request_builder)?;
+        Self::read_body(response)
+    }
+
+    /// Deserializes the response DTO, without reading the body of void responses
+    fn read_body<R: ServiceStackResponse>(response: reqwest::blocking::Response) -> Result<R> {
+        if R::IS_VOID {
+            response::void()
+        } else {
+            response::from_body(&response.bytes()?)
+        }
+    }
+
+    /// Authenticates and sends a request, turning unsuccessful responses into errors
+    fn execute(&self, request_builder: RequestBuilder) -> Result<reqwest::blocking::Response> {
+        let request_builder = match &self.bearer_token {
+            Some(token) => request_builder.bearer_auth(token),
+            None => request_builder,
+        };
+
+        let response = request_builder.send()?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().unwrap_or_default();
+            return Err(ServiceStackError::from_response_body(status.as_u16(), body));
+        }
+        Ok(response)
+    }
+}
@@ -0,0 +1,150 @@
+//! Streamed request bodies
+//!
+//! [`JsonServiceClient::send_body`](crate::JsonServiceClient::send_body) sends a
+//! [`RequestBody`] read from an `AsyncRead` or a `Stream` instead of serializing the DTO,
+//! so large uploads are not loaded into memory. The DTO fields not used by the route
+//! are sent on the query string, as ServiceStack expects for `IRequiresRequestStream`
+//! services.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use servicestack::{JsonServiceClient, ServiceStackRequest};
+//! use servicestack::{RequestBody, RequestOptions};
+//!
+//! # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, request: T) -> servicestack::Result<()> {
+//! let file = tokio::fs::File::open("backup.tar").await?;
+//! let length = file.metadata().await?.len();
+//! let body = RequestBody::from_reader(file)
+//!     .length(length)
+//!     .content_type("application/x-tar");
+//! let options = RequestOptions::new()
+//!     .on_upload_progress(|progress| println!("{:?}", progress.fraction()));
+//! let response = client.send_body_with(request, body, options).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use bytes::Bytes;
+use futures_util::TryStream;
+use reqwest::header::{HeaderValue, CONTENT_LENGTH, CONTENT_TYPE};
+use std::error::Error as StdError;
+use std::fmt;
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+
+/// The content type sent unless another is set
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// A raw request body, sent as-is
+pub struct RequestBody {
+    body: reqwest::Body,
+    length: Option<u64>,
+    content_type: Option<String>,
+}
+
+impl fmt::Debug for RequestBody {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestBody")
+            .field("length", &self.length)
+            .field("content_type", &self.content_type)
+            .finish()
+    }
+}
+
+impl RequestBody {
+    /// Creates a body from bytes in memory
+    pub fn from_bytes(bytes: impl Into<Bytes>) -> Self {
+        let bytes = bytes.into();
+        let length = bytes.len() as u64;
+        Self::new(reqwest::Body::from(bytes)).length(length)
+    }
+
+    /// Creates a body streamed from an async reader
+    ///
+    /// Set the [`length`](Self::length) when it is known, otherwise the body is sent
+    /// with chunked transfer encoding.
+    pub fn from_reader(reader: impl AsyncRead + Send + Sync + 'static) -> Self {
+        Self::new(reqwest::Body::wrap_stream(ReaderStream::new(reader)))
+    }
+
+    /// Creates a body from a stream of chunks
+    ///
+    /// Set the [`length`](Self::length) when it is known, otherwise the body is sent
+    /// with chunked transfer encoding.
+    pub fn from_stream<S>(stream: S) -> Self
+    where
+        S: TryStream + Send + Sync + 'static,
+        S::Error: Into<Box<dyn StdError + Send + Sync>>,
+        Bytes: From<S::Ok>,
+    {
+        Self::new(reqwest::Body::wrap_stream(stream))
+    }
+
+    fn new(body: reqwest::Body) -> Self {
+        Self {
+            body,
+            length: None,
+            content_type: None,
+        }
+    }
+
+    /// Sets the length of the body, sent as the `Content-Length`
+    pub fn length(mut self, length: u64) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Sets the content type (defaults to `application/octet-stream`)
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Sets the body, content type and length of a request
+    pub(crate) fn apply(
+        self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder> {
+        let content_type = self.content_type.as_deref().unwrap_or(DEFAULT_CONTENT_TYPE);
+        let content_type = HeaderValue::from_str(content_type)
+            .map_err(|e| ServiceStackError::InvalidHeader(format!("Content-Type: {}", e)))?;
+
+        let request_builder = request_builder
+            .header(CONTENT_TYPE, content_type)
+            .body(self.body);
+        Ok(match self.length {
+            Some(length) => request_builder.header(CONTENT_LENGTH, length),
+            None => request_builder,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let client = reqwest::Client::new();
+        let request = RequestBody::from_bytes("hello")
+            .apply(client.post("https://api.example.com/upload"))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()[CONTENT_TYPE], DEFAULT_CONTENT_TYPE);
+        assert_eq!(request.headers()[CONTENT_LENGTH], "5");
+        assert_eq!(request.body().unwrap().as_bytes(), Some(&b"hello"[..]));
+    }
+
+    #[test]
+    fn test_invalid_content_type() {
+        let client = reqwest::Client::new();
+        let result = RequestBody::from_reader(&b"hello"[..])
+            .content_type("text/plain\n")
+            .apply(client.post("https://api.example.com/upload"));
+        assert!(matches!(result, Err(ServiceStackError::InvalidHeader(_))));
+    }
+}
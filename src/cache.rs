@@ -0,0 +1,866 @@
+//! HTTP caching with `ETag`/`Last-Modified` revalidation and `Cache-Control` freshness
+//!
+//! Like ServiceStack's `CachedServiceClient`, an [`HttpCache`] set on a
+//! [`JsonServiceClient`](crate::JsonServiceClient) stores GET responses that carry an
+//! `ETag` or `Last-Modified` validator. Later GET requests for the same URL send
+//! `If-None-Match`/`If-Modified-Since`, and when the service answers `304 Not Modified`
+//! the cached body is deserialized instead, saving the bandwidth of resending it.
+//!
+//! With [`fresh_responses`](HttpCache::fresh_responses) enabled, responses with
+//! `Cache-Control: max-age` are also stored and served without a request while they are
+//! fresh. `no-store` responses are never stored, `no-cache` responses are always
+//! revalidated, and a response is only reused for requests with the same values of the
+//! headers named in its `Vary`. Responses to authenticated requests are kept per
+//! `Authorization` header, so they are never reused for other credentials. A
+//! [`shared`](HttpCache::shared) cache also skips `private` responses and prefers
+//! `s-maxage`. A single call can skip fresh responses with
+//! [`RequestOptions::bypass_cache`](crate::RequestOptions::bypass_cache).
+//!
+//! Whether a response is stored is decided from its status and headers, so responses
+//! that won't be stored, or whose body turns out larger than the store accepts, are
+//! handed back without buffering their body. Downloads bypass the cache. The cache runs
+//! after the client's request filters, so it sees the headers they add.
+//!
+//! Responses are kept in a [`CacheStore`]. The default [`MemoryCache`] evicts the least
+//! recently used responses once their total size exceeds its limit; implement
+//! `CacheStore` to share responses between clients or persist them.
+//!
+//! # Example
+//!
+//! ```
+//! use servicestack::{HttpCache, JsonServiceClient, MemoryCache};
+//!
+//! let cache = HttpCache::with_store(MemoryCache::new(50 * 1024 * 1024)).fresh_responses(true);
+//! let client = JsonServiceClient::builder("https://api.example.com")
+//!     .http_cache(cache.clone())
+//!     .build()
+//!     .unwrap();
+//!
+//! // ... after some requests
+//! let stats = cache.stats();
+//! println!("{} hits, {} misses", stats.hits, stats.misses);
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use crate::middleware::{Middleware, Next};
+use async_trait::async_trait;
+use bytes::{Bytes, BytesMut};
+use futures_util::{stream, StreamExt};
+use reqwest::header::{
+    HeaderMap, HeaderName, AGE, AUTHORIZATION, CACHE_CONTROL, CONTENT_LENGTH, DATE, ETAG, EXPIRES,
+    IF_MODIFIED_SINCE, IF_NONE_MATCH, LAST_MODIFIED, VARY,
+};
+use reqwest::{Method, ResponseBuilderExt, StatusCode, Url};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+/// The default size limit of a [`MemoryCache`], 10 MiB
+const DEFAULT_MAX_SIZE: usize = 10 * 1024 * 1024;
+
+/// Headers of a `304 Not Modified` response that replace the stored ones
+const REVALIDATED_HEADERS: [HeaderName; 6] =
+    [AGE, CACHE_CONTROL, DATE, ETAG, EXPIRES, LAST_MODIFIED];
+
+/// The `Cache-Control` directives that affect caching
+#[derive(Debug, Default, PartialEq, Eq)]
+struct CacheControl {
+    no_store: bool,
+    no_cache: bool,
+    private: bool,
+    public: bool,
+    must_revalidate: bool,
+    max_age: Option<u64>,
+    s_maxage: Option<u64>,
+}
+
+impl CacheControl {
+    /// Parses every `Cache-Control` header, ignoring unknown directives
+    fn parse(headers: &HeaderMap) -> Self {
+        let mut cache_control = Self::default();
+        let directives = headers
+            .get_all(CACHE_CONTROL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','));
+        for directive in directives {
+            let (name, value) = match directive.split_once('=') {
+                Some((name, value)) => (name.trim(), Some(value.trim().trim_matches('"'))),
+                None => (directive.trim(), None),
+            };
+            let seconds = value.and_then(|value| value.parse().ok());
+            match name.to_ascii_lowercase().as_str() {
+                "no-store" => cache_control.no_store = true,
+                "no-cache" => cache_control.no_cache = true,
+                "private" => cache_control.private = true,
+                "public" => cache_control.public = true,
+                "must-revalidate" => cache_control.must_revalidate = true,
+                "max-age" => cache_control.max_age = seconds,
+                "s-maxage" => cache_control.s_maxage = seconds,
+                _ => {}
+            }
+        }
+        cache_control
+    }
+
+    /// Returns how long a response stays fresh, if it may be served without a request
+    fn freshness_lifetime(&self, shared: bool) -> Option<Duration> {
+        if self.no_cache {
+            return None;
+        }
+        let seconds = match (shared, self.s_maxage) {
+            (true, Some(s_maxage)) => s_maxage,
+            _ => self.max_age?,
+        };
+        Some(Duration::from_secs(seconds))
+    }
+}
+
+/// A response stored in a [`CacheStore`]
+#[derive(Debug, Clone)]
+pub struct CachedResponse {
+    status: StatusCode,
+    headers: HeaderMap,
+    body: Bytes,
+    vary_headers: HeaderMap,
+    stored_at: SystemTime,
+}
+
+impl CachedResponse {
+    /// Creates a cached response, stored now
+    pub fn new(status: StatusCode, headers: HeaderMap, body: Bytes) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+            vary_headers: HeaderMap::new(),
+            stored_at: SystemTime::now(),
+        }
+    }
+
+    /// Sets the request headers named by the response's `Vary` header
+    ///
+    /// The response is only reused for requests with the same values.
+    pub fn with_vary_headers(mut self, vary_headers: HeaderMap) -> Self {
+        self.vary_headers = vary_headers;
+        self
+    }
+
+    /// Sets when the response was stored, e.g. when loading it from disk
+    pub fn with_stored_at(mut self, stored_at: SystemTime) -> Self {
+        self.stored_at = stored_at;
+        self
+    }
+
+    /// Returns the HTTP status code
+    pub fn status(&self) -> StatusCode {
+        self.status
+    }
+
+    /// Returns the response headers
+    pub fn headers(&self) -> &HeaderMap {
+        &self.headers
+    }
+
+    /// Returns the response body
+    pub fn body(&self) -> &Bytes {
+        &self.body
+    }
+
+    /// Returns the request headers named by the response's `Vary` header
+    pub fn vary_headers(&self) -> &HeaderMap {
+        &self.vary_headers
+    }
+
+    /// Returns when the response was stored or last revalidated
+    pub fn stored_at(&self) -> SystemTime {
+        self.stored_at
+    }
+
+    /// Returns the age of the response, including any `Age` it had when stored
+    pub fn age(&self) -> Duration {
+        let initial = self
+            .headers
+            .get(AGE)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.trim().parse().ok())
+            .map(Duration::from_secs)
+            .unwrap_or_default();
+        let elapsed = SystemTime::now()
+            .duration_since(self.stored_at)
+            .unwrap_or_default();
+        initial + elapsed
+    }
+
+    /// Returns the `ETag` header, if any
+    pub fn etag(&self) -> Option<&str> {
+        self.headers.get(ETAG)?.to_str().ok()
+    }
+
+    /// Returns the `Last-Modified` header, if any
+    pub fn last_modified(&self) -> Option<&str> {
+        self.headers.get(LAST_MODIFIED)?.to_str().ok()
+    }
+
+    /// Returns the approximate size in bytes of the body and headers
+    pub fn size(&self) -> usize {
+        let headers: usize = self
+            .headers
+            .iter()
+            .chain(self.vary_headers.iter())
+            .map(|(name, value)| name.as_str().len() + value.len())
+            .sum();
+        self.body.len() + headers
+    }
+
+    /// Returns whether the response can be served without a request
+    fn is_fresh(&self, shared: bool) -> bool {
+        CacheControl::parse(&self.headers)
+            .freshness_lifetime(shared)
+            .is_some_and(|lifetime| self.age() < lifetime)
+    }
+
+    /// Returns whether the request has the header values the response varies on
+    fn matches(&self, request: &HeaderMap) -> bool {
+        vary_names(&self.headers)
+            .iter()
+            .all(|name| request.get(name) == self.vary_headers.get(name))
+    }
+
+    /// Adds `If-None-Match`/`If-Modified-Since` for the stored validators
+    fn add_conditions(&self, headers: &mut HeaderMap) {
+        if let Some(etag) = self.headers.get(ETAG) {
+            headers.insert(IF_NONE_MATCH, etag.clone());
+        }
+        if let Some(last_modified) = self.headers.get(LAST_MODIFIED) {
+            headers.insert(IF_MODIFIED_SINCE, last_modified.clone());
+        }
+    }
+
+    /// Updates the stored headers from a `304 Not Modified` response
+    fn revalidate(&mut self, headers: &HeaderMap) {
+        self.headers.remove(AGE);
+        for name in REVALIDATED_HEADERS {
+            if let Some(value) = headers.get(&name) {
+                self.headers.insert(name, value.clone());
+            }
+        }
+        self.stored_at = SystemTime::now();
+    }
+
+    /// Rebuilds the response for the given URL
+    fn to_response(&self, url: Url) -> Result<reqwest::Response> {
+        rebuild(self.status, self.headers.clone(), url, self.body.clone())
+    }
+}
+
+/// Storage for cached responses, keyed by request
+///
+/// Implementations are shared between requests and must be thread-safe.
+pub trait CacheStore: Send + Sync {
+    /// Returns the response stored for a key
+    fn get(&self, key: &str) -> Option<CachedResponse>;
+
+    /// Stores a response, replacing any stored for the key
+    fn insert(&self, key: String, response: CachedResponse);
+
+    /// Removes the response stored for a key
+    fn remove(&self, key: &str);
+
+    /// Removes every stored response
+    fn clear(&self);
+
+    /// Returns the size in bytes of the largest response the store accepts, if limited
+    ///
+    /// Responses known to be larger are not read into memory to be stored.
+    fn max_entry_size(&self) -> Option<usize> {
+        None
+    }
+}
+
+/// An in-memory [`CacheStore`] that evicts the least recently used responses once
+/// their total size exceeds a limit
+pub struct MemoryCache {
+    max_size: usize,
+    inner: Mutex<Lru>,
+}
+
+#[derive(Default)]
+struct Lru {
+    entries: HashMap<String, (CachedResponse, u64)>,
+    recency: BTreeMap<u64, String>,
+    tick: u64,
+    size: usize,
+    evictions: u64,
+}
+
+impl Lru {
+    fn remove(&mut self, key: &str) -> Option<CachedResponse> {
+        let (response, tick) = self.entries.remove(key)?;
+        self.recency.remove(&tick);
+        self.size -= response.size();
+        Some(response)
+    }
+
+    fn touch(&mut self, key: &str) -> Option<&CachedResponse> {
+        self.tick += 1;
+        let (response, tick) = self.entries.get_mut(key)?;
+        self.recency.remove(tick);
+        *tick = self.tick;
+        self.recency.insert(self.tick, key.to_string());
+        Some(response)
+    }
+}
+
+impl Default for MemoryCache {
+    fn default() -> Self {
+        Self::new(DEFAULT_MAX_SIZE)
+    }
+}
+
+impl fmt::Debug for MemoryCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let lru = self.lock();
+        f.debug_struct("MemoryCache")
+            .field("max_size", &self.max_size)
+            .field("len", &lru.entries.len())
+            .field("size", &lru.size)
+            .finish()
+    }
+}
+
+impl MemoryCache {
+    /// Creates a cache holding up to `max_size` bytes of responses
+    ///
+    /// Responses larger than `max_size` are not stored.
+    pub fn new(max_size: usize) -> Self {
+        Self {
+            max_size,
+            inner: Mutex::new(Lru::default()),
+        }
+    }
+
+    /// Returns the size limit in bytes
+    pub fn max_size(&self) -> usize {
+        self.max_size
+    }
+
+    /// Returns the total size in bytes of the stored responses
+    pub fn size(&self) -> usize {
+        self.lock().size
+    }
+
+    /// Returns the number of stored responses
+    pub fn len(&self) -> usize {
+        self.lock().entries.len()
+    }
+
+    /// Returns whether no responses are stored
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Returns the number of responses evicted to stay within the size limit
+    pub fn evictions(&self) -> u64 {
+        self.lock().evictions
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Lru> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+impl CacheStore for MemoryCache {
+    fn get(&self, key: &str) -> Option<CachedResponse> {
+        self.lock().touch(key).cloned()
+    }
+
+    fn insert(&self, key: String, response: CachedResponse) {
+        let mut lru = self.lock();
+        lru.remove(&key);
+
+        let size = response.size();
+        if size > self.max_size {
+            return;
+        }
+        while lru.size + size > self.max_size {
+            let Some((_, oldest)) = lru.recency.pop_first() else {
+                break;
+            };
+            if let Some((response, _)) = lru.entries.remove(&oldest) {
+                lru.size -= response.size();
+                lru.evictions += 1;
+            }
+        }
+
+        lru.tick += 1;
+        let tick = lru.tick;
+        lru.recency.insert(tick, key.clone());
+        lru.entries.insert(key, (response, tick));
+        lru.size += size;
+    }
+
+    fn remove(&self, key: &str) {
+        self.lock().remove(key);
+    }
+
+    fn clear(&self) {
+        let mut lru = self.lock();
+        lru.entries.clear();
+        lru.recency.clear();
+        lru.size = 0;
+    }
+
+    fn max_entry_size(&self) -> Option<usize> {
+        Some(self.max_size)
+    }
+}
+
+/// Hit and miss counts of an [`HttpCache`]
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// GET requests answered from the cache
+    pub hits: u64,
+    /// GET requests that needed a full response from the service
+    pub misses: u64,
+}
+
+impl CacheStats {
+    /// Returns the fraction of GET requests answered from the cache
+    pub fn hit_rate(&self) -> f64 {
+        match self.hits + self.misses {
+            0 => 0.0,
+            total => self.hits as f64 / total as f64,
+        }
+    }
+}
+
+/// Caches GET responses and revalidates them with conditional requests
+///
+/// Clones share their store and statistics.
+#[derive(Clone)]
+pub struct HttpCache {
+    store: Arc<dyn CacheStore>,
+    fresh_responses: bool,
+    shared: bool,
+    hits: Arc<AtomicU64>,
+    misses: Arc<AtomicU64>,
+}
+
+impl Default for HttpCache {
+    fn default() -> Self {
+        Self::with_store(MemoryCache::default())
+    }
+}
+
+impl fmt::Debug for HttpCache {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HttpCache")
+            .field("fresh_responses", &self.fresh_responses)
+            .field("shared", &self.shared)
+            .field("stats", &self.stats())
+            .finish()
+    }
+}
+
+impl HttpCache {
+    /// Creates a cache backed by a 10 MiB [`MemoryCache`]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a cache backed by the given store
+    pub fn with_store(store: impl CacheStore + 'static) -> Self {
+        Self {
+            store: Arc::new(store),
+            fresh_responses: false,
+            shared: false,
+            hits: Arc::new(AtomicU64::new(0)),
+            misses: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    /// Serves responses without a request while `Cache-Control: max-age` says they are
+    /// fresh (defaults to false)
+    pub fn fresh_responses(mut self, enabled: bool) -> Self {
+        self.fresh_responses = enabled;
+        self
+    }
+
+    /// Marks the cache as shared between users (defaults to false)
+    ///
+    /// A shared cache does not store `private` responses, nor responses to
+    /// authenticated requests unless they are `public` or have `s-maxage`, and prefers
+    /// `s-maxage` to `max-age`.
+    pub fn shared(mut self, shared: bool) -> Self {
+        self.shared = shared;
+        self
+    }
+
+    /// Returns the store holding the cached responses
+    pub fn store(&self) -> &dyn CacheStore {
+        self.store.as_ref()
+    }
+
+    /// Returns the hit and miss counts
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Resets the hit and miss counts to zero
+    pub fn reset_stats(&self) {
+        self.hits.store(0, Ordering::Relaxed);
+        self.misses.store(0, Ordering::Relaxed);
+    }
+
+    /// Removes every cached response
+    pub fn clear(&self) {
+        self.store.clear();
+    }
+
+    /// Returns the cache key of a request, or `None` if it is not cached
+    ///
+    /// The key is the URL, followed by the SHA-256 hash of the `Authorization` header of
+    /// authenticated requests so stores never see the credentials themselves. Keys are
+    /// stable across builds, so persistent stores can keep them.
+    fn key(request: &reqwest::Request) -> Option<String> {
+        let headers = request.headers();
+        // Requests with their own conditions are left to the caller
+        if request.method() != Method::GET
+            || headers.contains_key(IF_NONE_MATCH)
+            || headers.contains_key(IF_MODIFIED_SINCE)
+        {
+            return None;
+        }
+        let url = request.url().to_string();
+        match headers.get(AUTHORIZATION) {
+            Some(authorization) => {
+                let digest = Sha256::digest(authorization.as_bytes());
+                Some(format!("{} {:x}", url, digest))
+            }
+            None => Some(url),
+        }
+    }
+
+    /// Returns whether a response to a request may be stored, from its headers alone
+    ///
+    /// A stored response needs a validator to be revalidated, or a freshness lifetime
+    /// when fresh responses are served.
+    fn is_storable(&self, request: &HeaderMap, response: &HeaderMap) -> bool {
+        let cache_control = CacheControl::parse(response);
+        if cache_control.no_store
+            || CacheControl::parse(request).no_store
+            || vary_names(response).iter().any(|name| name == "*")
+        {
+            return false;
+        }
+        if self.shared {
+            let authorized = request.contains_key(AUTHORIZATION)
+                && !cache_control.public
+                && !cache_control.must_revalidate
+                && cache_control.s_maxage.is_none();
+            if cache_control.private || authorized {
+                return false;
+            }
+        }
+        let has_validator = response.contains_key(ETAG) || response.contains_key(LAST_MODIFIED);
+        let fresh = self.fresh_responses && cache_control.freshness_lifetime(self.shared).is_some();
+        has_validator || fresh
+    }
+
+    /// Stores a cacheable response, returning it rebuilt
+    ///
+    /// Responses that won't be stored are returned without reading their body, and a
+    /// body that grows past the store's size limit is passed on as a stream.
+    async fn store_response(
+        &self,
+        key: String,
+        request: &HeaderMap,
+        response: reqwest::Response,
+    ) -> Result<reqwest::Response> {
+        let max_size = self.store.max_entry_size().unwrap_or(usize::MAX);
+        let content_length = response
+            .headers()
+            .get(CONTENT_LENGTH)
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| value.parse::<u64>().ok());
+        if response.status() != StatusCode::OK
+            || !self.is_storable(request, response.headers())
+            || content_length.is_some_and(|length| length > max_size as u64)
+        {
+            self.store.remove(&key);
+            return Ok(response);
+        }
+
+        let vary_headers = vary_names(response.headers())
+            .into_iter()
+            .filter_map(|name| Some((name.clone(), request.get(&name)?.clone())))
+            .collect();
+        let status = response.status();
+        let url = response.url().clone();
+        let headers = response.headers().clone();
+
+        let mut body = BytesMut::new();
+        let mut chunks = response.bytes_stream();
+        while let Some(chunk) = chunks.next().await {
+            body.extend_from_slice(&chunk?);
+            if body.len() > max_size {
+                self.store.remove(&key);
+                let read = stream::once(async move { Ok(body.freeze()) });
+                let body = reqwest::Body::wrap_stream(read.chain(chunks));
+                return rebuild(status, headers, url, body);
+            }
+        }
+
+        let cached =
+            CachedResponse::new(status, headers, body.freeze()).with_vary_headers(vary_headers);
+        self.store.insert(key, cached.clone());
+        cached.to_response(url)
+    }
+}
+
+/// Builds a response from its parts
+fn rebuild(
+    status: StatusCode,
+    headers: HeaderMap,
+    url: Url,
+    body: impl Into<reqwest::Body>,
+) -> Result<reqwest::Response> {
+    let mut response = http::Response::builder()
+        .status(status)
+        .url(url)
+        .body(body.into())
+        .map_err(|e| ServiceStackError::Other(format!("Invalid cached response: {}", e)))?;
+    *response.headers_mut() = headers;
+    Ok(reqwest::Response::from(response))
+}
+
+/// Returns the header names listed in `Vary`, lowercased, or `*`
+fn vary_names(headers: &HeaderMap) -> Vec<HeaderName> {
+    headers
+        .get_all(VARY)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| match name.trim() {
+            "*" => Some(HeaderName::from_static("*")),
+            name => HeaderName::from_bytes(name.as_bytes()).ok(),
+        })
+        .collect()
+}
+
+#[async_trait]
+impl Middleware for HttpCache {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response> {
+        let Some(key) = Self::key(&request) else {
+            return next.run(request).await;
+        };
+
+        let url = request.url().clone();
+        let cached = self
+            .store
+            .get(&key)
+            .filter(|cached| cached.matches(request.headers()));
+        if let Some(cached) = &cached {
+            // `Cache-Control: no-cache` on the request asks for a revalidated response
+            let bypass = CacheControl::parse(request.headers()).no_cache;
+            if self.fresh_responses && !bypass && cached.is_fresh(self.shared) {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                return cached.to_response(url);
+            }
+            cached.add_conditions(request.headers_mut());
+        }
+
+        let request_headers = request.headers().clone();
+        let response = next.run(request).await?;
+        match cached {
+            Some(mut cached) if response.status() == StatusCode::NOT_MODIFIED => {
+                self.hits.fetch_add(1, Ordering::Relaxed);
+                cached.revalidate(response.headers());
+                self.store.insert(key, cached.clone());
+                cached.to_response(url)
+            }
+            _ => {
+                self.misses.fetch_add(1, Ordering::Relaxed);
+                self.store_response(key, &request_headers, response).await
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    fn response(etag: &str, body: &'static str) -> CachedResponse {
+        let mut headers = HeaderMap::new();
+        headers.insert(ETAG, HeaderValue::from_str(etag).unwrap());
+        CachedResponse::new(StatusCode::OK, headers, Bytes::from(body))
+    }
+
+    #[test]
+    fn test_memory_cache_evicts_least_recently_used() {
+        let entry_size = response("\"1\"", "aaaa").size();
+        let cache = MemoryCache::new(entry_size * 2);
+        cache.insert("a".to_string(), response("\"1\"", "aaaa"));
+        cache.insert("b".to_string(), response("\"2\"", "bbbb"));
+
+        // Reading `a` makes `b` the least recently used
+        assert!(cache.get("a").is_some());
+        cache.insert("c".to_string(), response("\"3\"", "cccc"));
+
+        assert!(cache.get("b").is_none());
+        assert_eq!(cache.get("a").unwrap().etag(), Some("\"1\""));
+        assert_eq!(cache.get("c").unwrap().body(), &Bytes::from("cccc"));
+        assert_eq!(cache.len(), 2);
+        assert_eq!(cache.size(), entry_size * 2);
+        assert_eq!(cache.evictions(), 1);
+    }
+
+    #[test]
+    fn test_memory_cache_skips_oversized_responses() {
+        let cache = MemoryCache::new(8);
+        cache.insert("a".to_string(), response("\"1\"", "too large to store"));
+        assert!(cache.is_empty());
+        assert_eq!(cache.size(), 0);
+    }
+
+    #[test]
+    fn test_memory_cache_replace_and_clear() {
+        let cache = MemoryCache::default();
+        cache.insert("a".to_string(), response("\"1\"", "old"));
+        cache.insert("a".to_string(), response("\"2\"", "new"));
+        assert_eq!(cache.len(), 1);
+        assert_eq!(cache.size(), response("\"2\"", "new").size());
+        assert_eq!(cache.get("a").unwrap().etag(), Some("\"2\""));
+
+        cache.clear();
+        assert!(cache.is_empty());
+        assert_eq!(cache.size(), 0);
+    }
+
+    #[test]
+    fn test_no_store() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("private, No-Store"));
+        assert!(CacheControl::parse(&headers).no_store);
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+        assert!(!CacheControl::parse(&headers).no_store);
+    }
+
+    #[test]
+    fn test_freshness_lifetime() {
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            CACHE_CONTROL,
+            HeaderValue::from_static("public, max-age=60, s-maxage=\"300\""),
+        );
+        let cache_control = CacheControl::parse(&headers);
+        assert_eq!(
+            cache_control.freshness_lifetime(false),
+            Some(Duration::from_secs(60))
+        );
+        assert_eq!(
+            cache_control.freshness_lifetime(true),
+            Some(Duration::from_secs(300))
+        );
+
+        headers.append(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        assert_eq!(
+            CacheControl::parse(&headers).freshness_lifetime(false),
+            None
+        );
+    }
+
+    #[test]
+    fn test_is_fresh_accounts_for_age() {
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+        let cached = CachedResponse::new(StatusCode::OK, headers.clone(), Bytes::new());
+        assert!(cached.is_fresh(false));
+
+        let stored_at = SystemTime::now() - Duration::from_secs(61);
+        assert!(!cached.clone().with_stored_at(stored_at).is_fresh(false));
+
+        headers.insert(AGE, HeaderValue::from_static("60"));
+        let cached = CachedResponse::new(StatusCode::OK, headers, Bytes::new());
+        assert!(!cached.is_fresh(false));
+    }
+
+    #[test]
+    fn test_vary_matching() {
+        let mut headers = HeaderMap::new();
+        headers.insert(VARY, HeaderValue::from_static("Accept-Language"));
+        let mut vary_headers = HeaderMap::new();
+        vary_headers.insert("accept-language", HeaderValue::from_static("en"));
+        let cached = CachedResponse::new(StatusCode::OK, headers, Bytes::new())
+            .with_vary_headers(vary_headers);
+
+        let mut request = HeaderMap::new();
+        assert!(!cached.matches(&request));
+        request.insert("accept-language", HeaderValue::from_static("en"));
+        assert!(cached.matches(&request));
+        request.insert("accept-language", HeaderValue::from_static("fr"));
+        assert!(!cached.matches(&request));
+    }
+
+    #[test]
+    fn test_is_storable_needs_validator_or_freshness() {
+        let request = HeaderMap::new();
+        let mut headers = HeaderMap::new();
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("max-age=60"));
+        assert!(!HttpCache::new().is_storable(&request, &headers));
+        assert!(HttpCache::new()
+            .fresh_responses(true)
+            .is_storable(&request, &headers));
+
+        headers.insert(ETAG, HeaderValue::from_static("\"1\""));
+        assert!(HttpCache::new().is_storable(&request, &headers));
+        headers.insert(CACHE_CONTROL, HeaderValue::from_static("no-store"));
+        assert!(!HttpCache::new().is_storable(&request, &headers));
+    }
+
+    #[test]
+    fn test_key_includes_authorization() {
+        let request = |token: Option<&str>| {
+            let client = reqwest::Client::new();
+            let mut builder = client.get("https://api.example.com/users/1");
+            if let Some(token) = token {
+                builder = builder.bearer_auth(token);
+            }
+            builder.build().unwrap()
+        };
+        let anonymous = HttpCache::key(&request(None)).unwrap();
+        let alice = HttpCache::key(&request(Some("alice"))).unwrap();
+        let bob = HttpCache::key(&request(Some("bob"))).unwrap();
+
+        assert_eq!(anonymous, "https://api.example.com/users/1");
+        assert_ne!(alice, anonymous);
+        assert_ne!(alice, bob);
+        assert!(!alice.contains("alice"));
+        assert_eq!(HttpCache::key(&request(Some("alice"))).unwrap(), alice);
+        // Keys must not change between builds, since stores may persist them
+        assert_eq!(
+            alice,
+            "https://api.example.com/users/1 9d7cce461e4b2f090a3d686b4ae72d25ea18e93573d2772bb52ff548e6262aa3"
+        );
+    }
+
+    #[test]
+    fn test_hit_rate() {
+        assert_eq!(CacheStats::default().hit_rate(), 0.0);
+        let stats = CacheStats { hits: 3, misses: 1 };
+        assert_eq!(stats.hit_rate(), 0.75);
+    }
+}
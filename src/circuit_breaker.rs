@@ -0,0 +1,417 @@
+//! Circuit breaker for failing fast when a service is unavailable
+//!
+//! A [`CircuitBreaker`] set on a [`JsonServiceClient`](crate::JsonServiceClient) tracks the
+//! outcome of recent requests to its base URL. Once the failure rate reaches the threshold
+//! the circuit opens and requests fail immediately with [`ServiceStackError::CircuitOpen`]
+//! instead of waiting on a service that is down. After the open duration the circuit is
+//! half-open and lets a probe request through: a success closes the circuit, a failure
+//! opens it again.
+//!
+//! Server errors (`5xx`), timeouts and connection errors count as failures; other
+//! error responses such as `404` or validation errors mean the service is up and
+//! count as successes.
+//!
+//! Each `CircuitBreaker` keeps its own state, which its clones share. Clients built
+//! separately for the same base URL count failures independently unless they are given
+//! clones of one breaker, which also lets the caller keep a clone to monitor the circuit.
+//!
+//! # Example
+//!
+//! ```
+//! use servicestack::{CircuitBreaker, CircuitState, JsonServiceClient};
+//! use std::time::Duration;
+//!
+//! let client = JsonServiceClient::builder("https://api.example.com")
+//!     .circuit_breaker(
+//!         CircuitBreaker::new()
+//!             .failure_rate_threshold(0.5)
+//!             .open_duration(Duration::from_secs(10)),
+//!     )
+//!     .build()
+//!     .unwrap();
+//!
+//! assert_eq!(client.circuit_state(), Some(CircuitState::Closed));
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use crate::middleware::{Middleware, Next};
+use async_trait::async_trait;
+use std::collections::VecDeque;
+use std::fmt;
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::{Duration, Instant};
+
+/// The state of a [`CircuitBreaker`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent and their outcomes recorded
+    Closed,
+    /// Requests fail fast without being sent
+    Open,
+    /// A limited number of probe requests are sent to test whether the service recovered
+    HalfOpen,
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let state = match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half-open",
+        };
+        f.write_str(state)
+    }
+}
+
+/// Opens after a failure rate is reached and fails requests fast while open
+///
+/// The state belongs to the breaker and its clones, not to a base URL: give every client
+/// for a service a clone of the same breaker to have them share one circuit.
+#[derive(Clone)]
+pub struct CircuitBreaker {
+    failure_rate_threshold: f64,
+    minimum_requests: usize,
+    window_size: usize,
+    open_duration: Duration,
+    half_open_requests: u32,
+    inner: Arc<Mutex<Inner>>,
+}
+
+#[derive(Debug)]
+struct Inner {
+    state: CircuitState,
+    outcomes: VecDeque<bool>,
+    opened_at: Option<Instant>,
+    probes_in_flight: u32,
+    probe_successes: u32,
+}
+
+impl Default for CircuitBreaker {
+    fn default() -> Self {
+        Self {
+            failure_rate_threshold: 0.5,
+            minimum_requests: 10,
+            window_size: 20,
+            open_duration: Duration::from_secs(30),
+            half_open_requests: 1,
+            inner: Arc::new(Mutex::new(Inner {
+                state: CircuitState::Closed,
+                outcomes: VecDeque::new(),
+                opened_at: None,
+                probes_in_flight: 0,
+                probe_successes: 0,
+            })),
+        }
+    }
+}
+
+impl fmt::Debug for CircuitBreaker {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("CircuitBreaker")
+            .field("failure_rate_threshold", &self.failure_rate_threshold)
+            .field("minimum_requests", &self.minimum_requests)
+            .field("window_size", &self.window_size)
+            .field("open_duration", &self.open_duration)
+            .field("half_open_requests", &self.half_open_requests)
+            .field("state", &self.state())
+            .finish()
+    }
+}
+
+impl CircuitBreaker {
+    /// Creates a closed circuit breaker that opens when half of the last 20 requests
+    /// failed (after at least 10), and stays open for 30 seconds
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the failure rate, between `0.0` and `1.0`, at which the circuit opens
+    pub fn failure_rate_threshold(mut self, threshold: f64) -> Self {
+        self.failure_rate_threshold = threshold.clamp(0.0, 1.0);
+        self
+    }
+
+    /// Sets the number of requests that must be recorded before the circuit can open
+    pub fn minimum_requests(mut self, minimum_requests: usize) -> Self {
+        self.minimum_requests = minimum_requests.max(1);
+        self
+    }
+
+    /// Sets the number of most recent requests the failure rate is calculated from
+    pub fn window_size(mut self, window_size: usize) -> Self {
+        self.window_size = window_size.max(1);
+        self
+    }
+
+    /// Sets how long the circuit stays open before letting probe requests through
+    pub fn open_duration(mut self, duration: Duration) -> Self {
+        self.open_duration = duration;
+        self
+    }
+
+    /// Sets the number of successful probes needed to close a half-open circuit
+    pub fn half_open_requests(mut self, requests: u32) -> Self {
+        self.half_open_requests = requests.max(1);
+        self
+    }
+
+    /// Returns the current state of the circuit
+    pub fn state(&self) -> CircuitState {
+        let mut inner = self.lock();
+        self.refresh(&mut inner, Instant::now());
+        inner.state
+    }
+
+    /// Returns the failure rate of the recorded requests, between `0.0` and `1.0`
+    pub fn failure_rate(&self) -> f64 {
+        Self::rate(&self.lock().outcomes)
+    }
+
+    /// Closes the circuit and forgets all recorded requests
+    pub fn reset(&self) {
+        let mut inner = self.lock();
+        Self::close(&mut inner);
+    }
+
+    /// Returns whether the error means the service is unavailable
+    pub fn is_failure(error: &ServiceStackError) -> bool {
+        match error {
+            ServiceStackError::ApiError { status, .. } => *status >= 500,
+            ServiceStackError::RequestError(e) => {
+                e.is_timeout() || e.is_connect() || e.is_request()
+            }
+            _ => false,
+        }
+    }
+
+    /// Admits a request, or fails with `CircuitOpen` while the circuit is open
+    fn acquire(&self, now: Instant) -> Result<Permit<'_>> {
+        let mut inner = self.lock();
+        self.refresh(&mut inner, now);
+        match inner.state {
+            CircuitState::Closed => Ok(Permit {
+                breaker: self,
+                probe: false,
+            }),
+            CircuitState::HalfOpen
+                if inner.probes_in_flight + inner.probe_successes < self.half_open_requests =>
+            {
+                inner.probes_in_flight += 1;
+                Ok(Permit {
+                    breaker: self,
+                    probe: true,
+                })
+            }
+            _ => {
+                let retry_after = inner
+                    .opened_at
+                    .map(|opened_at| {
+                        (opened_at + self.open_duration).saturating_duration_since(now)
+                    })
+                    .unwrap_or_default();
+                Err(ServiceStackError::CircuitOpen { retry_after })
+            }
+        }
+    }
+
+    /// Records the outcome of an admitted request
+    fn record(&self, probe: bool, success: bool, now: Instant) {
+        let mut inner = self.lock();
+        if probe {
+            inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+            if inner.state != CircuitState::HalfOpen {
+                return;
+            }
+            if !success {
+                Self::open(&mut inner, now);
+            } else {
+                inner.probe_successes += 1;
+                if inner.probe_successes >= self.half_open_requests {
+                    Self::close(&mut inner);
+                }
+            }
+            return;
+        }
+
+        if inner.state != CircuitState::Closed {
+            return;
+        }
+        inner.outcomes.push_back(success);
+        while inner.outcomes.len() > self.window_size {
+            inner.outcomes.pop_front();
+        }
+        if inner.outcomes.len() >= self.minimum_requests
+            && Self::rate(&inner.outcomes) >= self.failure_rate_threshold
+        {
+            Self::open(&mut inner, now);
+        }
+    }
+
+    /// Moves an open circuit to half-open once the open duration has passed
+    fn refresh(&self, inner: &mut Inner, now: Instant) {
+        if let (CircuitState::Open, Some(opened_at)) = (inner.state, inner.opened_at) {
+            if now.saturating_duration_since(opened_at) >= self.open_duration {
+                inner.state = CircuitState::HalfOpen;
+                inner.probes_in_flight = 0;
+                inner.probe_successes = 0;
+            }
+        }
+    }
+
+    fn open(inner: &mut Inner, now: Instant) {
+        inner.state = CircuitState::Open;
+        inner.opened_at = Some(now);
+        inner.outcomes.clear();
+    }
+
+    fn close(inner: &mut Inner) {
+        inner.state = CircuitState::Closed;
+        inner.opened_at = None;
+        inner.outcomes.clear();
+        inner.probes_in_flight = 0;
+        inner.probe_successes = 0;
+    }
+
+    fn rate(outcomes: &VecDeque<bool>) -> f64 {
+        if outcomes.is_empty() {
+            return 0.0;
+        }
+        let failures = outcomes.iter().filter(|success| !**success).count();
+        failures as f64 / outcomes.len() as f64
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Inner> {
+        self.inner.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
+
+/// An admitted request; releases its probe slot if dropped before completing
+struct Permit<'a> {
+    breaker: &'a CircuitBreaker,
+    probe: bool,
+}
+
+impl Permit<'_> {
+    fn complete(self, success: bool) {
+        self.breaker.record(self.probe, success, Instant::now());
+        std::mem::forget(self);
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        if self.probe {
+            let mut inner = self.breaker.lock();
+            inner.probes_in_flight = inner.probes_in_flight.saturating_sub(1);
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for CircuitBreaker {
+    async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response> {
+        let permit = self.acquire(Instant::now())?;
+        let result = next.run(request).await;
+        let success = match &result {
+            Ok(_) => true,
+            Err(error) => !Self::is_failure(error),
+        };
+        permit.complete(success);
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker() -> CircuitBreaker {
+        CircuitBreaker::new()
+            .minimum_requests(4)
+            .window_size(4)
+            .failure_rate_threshold(0.5)
+            .open_duration(Duration::from_secs(10))
+    }
+
+    fn run(breaker: &CircuitBreaker, success: bool, now: Instant) -> Result<()> {
+        breaker.acquire(now)?;
+        breaker.record(false, success, now);
+        Ok(())
+    }
+
+    #[test]
+    fn test_opens_at_failure_rate() {
+        let breaker = breaker();
+        let now = Instant::now();
+        run(&breaker, true, now).unwrap();
+        run(&breaker, false, now).unwrap();
+        run(&breaker, true, now).unwrap();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        run(&breaker, false, now).unwrap();
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        let error = breaker.acquire(now).map(|_| ()).unwrap_err();
+        assert!(matches!(
+            error,
+            ServiceStackError::CircuitOpen { retry_after } if retry_after == Duration::from_secs(10)
+        ));
+    }
+
+    #[test]
+    fn test_window_forgets_old_outcomes() {
+        let breaker = breaker();
+        let now = Instant::now();
+        run(&breaker, false, now).unwrap();
+        for _ in 0..4 {
+            run(&breaker, true, now).unwrap();
+        }
+        assert_eq!(breaker.failure_rate(), 0.0);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_half_open_probe() {
+        let breaker = breaker();
+        let now = Instant::now();
+        for _ in 0..4 {
+            run(&breaker, false, now).unwrap();
+        }
+
+        let later = now + Duration::from_secs(10);
+        let probe = breaker.acquire(later).unwrap();
+        assert!(probe.probe);
+        assert!(breaker.acquire(later).is_err());
+        drop(probe);
+
+        // A failed probe opens the circuit again
+        breaker.acquire(later).unwrap().complete(false);
+        assert_eq!(breaker.lock().state, CircuitState::Open);
+
+        let later = later + Duration::from_secs(10);
+        breaker.acquire(later).unwrap().complete(true);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_failures() {
+        let server_error = ServiceStackError::from_response_body(503, String::new());
+        let not_found = ServiceStackError::from_response_body(404, String::new());
+        assert!(CircuitBreaker::is_failure(&server_error));
+        assert!(!CircuitBreaker::is_failure(&not_found));
+        assert!(!CircuitBreaker::is_failure(&ServiceStackError::message(
+            "other"
+        )));
+    }
+
+    #[test]
+    fn test_clones_share_state() {
+        let breaker = breaker();
+        let clone = breaker.clone();
+        for _ in 0..4 {
+            run(&breaker, false, Instant::now()).unwrap();
+        }
+        assert_eq!(clone.state(), CircuitState::Open);
+        clone.reset();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
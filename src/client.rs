@@ -1,8 +1,59 @@
+use crate::api_response::ApiResponse;
+use crate::api_result::ApiResult;
+use crate::batch;
+use crate::body::RequestBody;
+use crate::cache::{CacheStats, HttpCache};
+use crate::circuit_breaker::{CircuitBreaker, CircuitState};
+use crate::compression::RequestCompression;
+use crate::download::Download;
 use crate::error::{Result, ServiceStackError};
-use crate::traits::ServiceStackRequest;
+use crate::filter::{RequestFilter, ResponseFilter};
+use crate::head::HeadResponse;
+use crate::middleware::{BearerAuth, ErrorMapping, Filters, Middleware, Next};
+use crate::options::RequestOptions;
+use crate::progress::{ProgressCallback, UploadProgress};
+use crate::query;
+use crate::response;
+use crate::retry::{Retry, RetryPolicy};
+use crate::route;
+use crate::traits::{HttpMethod, ServiceStackRequest, ServiceStackResponse};
+use crate::upload::{self, FilePart};
+use reqwest::header::{Entry, HeaderMap};
 use reqwest::Client;
 use serde::{de::DeserializeOwned, Serialize};
-use std::time::Duration;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+mod builder;
+mod concurrent;
+
+pub use builder::JsonServiceClientBuilder;
+
+/// ServiceStack's pre-defined routes, used for requests without a path or route templates
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum PredefinedRoutes {
+    /// `/api/{Operation}`
+    #[default]
+    Api,
+    /// `/json/reply/{Operation}`
+    JsonReply,
+    /// `{base path}/{Operation}`, for services that host the pre-defined routes elsewhere
+    BasePath(String),
+}
+
+impl PredefinedRoutes {
+    /// Returns the pre-defined path for the given operation name
+    pub fn path(&self, operation_name: &str) -> String {
+        match self {
+            PredefinedRoutes::Api => format!("/api/{}", operation_name),
+            PredefinedRoutes::JsonReply => format!("/json/reply/{}", operation_name),
+            PredefinedRoutes::BasePath(base_path) => {
+                format!("{}/{}", base_path.trim_end_matches('/'), operation_name)
+            }
+        }
+    }
+}
 
 /// JsonServiceClient for making typed API requests to ServiceStack services
 ///
@@ -20,7 +71,16 @@ use std::time::Duration;
 pub struct JsonServiceClient {
     base_url: String,
     http_client: Client,
+    default_headers: HeaderMap,
     bearer_token: Option<String>,
+    predefined_routes: PredefinedRoutes,
+    middleware: Vec<Arc<dyn Middleware>>,
+    request_filters: Vec<Arc<dyn RequestFilter>>,
+    response_filters: Vec<Arc<dyn ResponseFilter>>,
+    retry_policy: Option<RetryPolicy>,
+    circuit_breaker: Option<CircuitBreaker>,
+    request_compression: Option<RequestCompression>,
+    http_cache: Option<HttpCache>,
 }
 
 impl JsonServiceClient {
@@ -46,13 +106,44 @@ impl JsonServiceClient {
         Self {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             http_client,
+            default_headers: HeaderMap::new(),
             bearer_token: None,
+            predefined_routes: PredefinedRoutes::default(),
+            middleware: Vec::new(),
+            request_filters: Vec::new(),
+            response_filters: Vec::new(),
+            retry_policy: None,
+            circuit_breaker: None,
+            request_compression: None,
+            http_cache: None,
         }
     }
 
+    /// Creates a builder for configuring a JsonServiceClient
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use servicestack::JsonServiceClient;
+    /// use std::time::Duration;
+    ///
+    /// let client = JsonServiceClient::builder("https://api.example.com")
+    ///     .timeout(Duration::from_secs(60))
+    ///     .user_agent("my-app/1.0")
+    ///     .default_header("X-Tenant", "acme")
+    ///     .bearer_token("your-token-here")
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder(base_url: impl Into<String>) -> JsonServiceClientBuilder {
+        JsonServiceClientBuilder::new(base_url)
+    }
+
     /// Creates a new JsonServiceClient with a custom reqwest Client
     ///
-    /// This allows you to configure the HTTP client with custom settings
+    /// This allows you to configure the HTTP client with custom settings. Default headers
+    /// of a custom client are not visible to middleware such as the [`HttpCache`]; set
+    /// them with [`JsonServiceClientBuilder::default_header`] instead.
     ///
     /// # Arguments
     ///
@@ -62,7 +153,16 @@ impl JsonServiceClient {
         Self {
             base_url: base_url.into().trim_end_matches('/').to_string(),
             http_client,
+            default_headers: HeaderMap::new(),
             bearer_token: None,
+            predefined_routes: PredefinedRoutes::default(),
+            middleware: Vec::new(),
+            request_filters: Vec::new(),
+            response_filters: Vec::new(),
+            retry_policy: None,
+            circuit_breaker: None,
+            request_compression: None,
+            http_cache: None,
         }
     }
 
@@ -89,6 +189,172 @@ impl JsonServiceClient {
         self.bearer_token = None;
     }
 
+    /// Sets the pre-defined routes used for requests without a path or route templates
+    ///
+    /// Defaults to [`PredefinedRoutes::Api`] (`/api/{Operation}`).
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use servicestack::{JsonServiceClient, PredefinedRoutes};
+    ///
+    /// let mut client = JsonServiceClient::new("https://api.example.com");
+    /// client.set_predefined_routes(PredefinedRoutes::JsonReply);
+    /// ```
+    pub fn set_predefined_routes(&mut self, routes: PredefinedRoutes) {
+        self.predefined_routes = routes;
+    }
+
+    /// Returns the pre-defined routes used for requests without a path or route templates
+    pub fn predefined_routes(&self) -> &PredefinedRoutes {
+        &self.predefined_routes
+    }
+
+    /// Registers a filter that can inspect and change every outgoing request
+    ///
+    /// Filters run in registration order, after the bearer token has been applied.
+    /// See the [`filter`](crate::filter) module for an example.
+    pub fn add_request_filter(&mut self, filter: impl RequestFilter + 'static) {
+        self.request_filters.push(Arc::new(filter));
+    }
+
+    /// Registers a filter that can inspect every raw response before deserialization
+    ///
+    /// Filters run in registration order for both successful and unsuccessful responses.
+    pub fn add_response_filter(&mut self, filter: impl ResponseFilter + 'static) {
+        self.response_filters.push(Arc::new(filter));
+    }
+
+    /// Sets the policy for retrying transient failures
+    ///
+    /// Only idempotent methods (GET, HEAD, OPTIONS, PUT, DELETE) are retried unless
+    /// the request type overrides [`ServiceStackRequest::retryable`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use servicestack::{JsonServiceClient, RetryPolicy};
+    ///
+    /// let mut client = JsonServiceClient::new("https://api.example.com");
+    /// client.set_retry_policy(RetryPolicy::new().max_attempts(4));
+    /// ```
+    pub fn set_retry_policy(&mut self, policy: RetryPolicy) {
+        self.retry_policy = Some(policy);
+    }
+
+    /// Clears the retry policy, so failed requests are not retried
+    pub fn clear_retry_policy(&mut self) {
+        self.retry_policy = None;
+    }
+
+    /// Returns the policy for retrying transient failures, if any
+    pub fn retry_policy(&self) -> Option<&RetryPolicy> {
+        self.retry_policy.as_ref()
+    }
+
+    /// Sets a circuit breaker that fails requests fast while the service is unavailable
+    ///
+    /// While the circuit is open requests fail with [`ServiceStackError::CircuitOpen`]
+    /// without being sent. Clients for the same base URL share a circuit only when they
+    /// are given clones of the same breaker. See the
+    /// [`circuit_breaker`](crate::circuit_breaker) module for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use servicestack::{CircuitBreaker, JsonServiceClient};
+    ///
+    /// let mut client = JsonServiceClient::new("https://api.example.com");
+    /// client.set_circuit_breaker(CircuitBreaker::new().failure_rate_threshold(0.25));
+    /// ```
+    pub fn set_circuit_breaker(&mut self, breaker: CircuitBreaker) {
+        self.circuit_breaker = Some(breaker);
+    }
+
+    /// Removes the circuit breaker
+    pub fn clear_circuit_breaker(&mut self) {
+        self.circuit_breaker = None;
+    }
+
+    /// Returns the circuit breaker, if any
+    pub fn circuit_breaker(&self) -> Option<&CircuitBreaker> {
+        self.circuit_breaker.as_ref()
+    }
+
+    /// Returns the current state of the circuit breaker, if any
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.circuit_breaker.as_ref().map(CircuitBreaker::state)
+    }
+
+    /// Compresses request bodies at or above a minimum size
+    ///
+    /// See the [`compression`](crate::compression) module for the supported algorithms.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use servicestack::{Compression, JsonServiceClient, RequestCompression};
+    ///
+    /// let mut client = JsonServiceClient::new("https://api.example.com");
+    /// client.set_request_compression(RequestCompression::new(Compression::Gzip));
+    /// ```
+    pub fn set_request_compression(&mut self, compression: RequestCompression) {
+        self.request_compression = Some(compression);
+    }
+
+    /// Stops compressing request bodies
+    pub fn clear_request_compression(&mut self) {
+        self.request_compression = None;
+    }
+
+    /// Returns the request body compression, if any
+    pub fn request_compression(&self) -> Option<&RequestCompression> {
+        self.request_compression.as_ref()
+    }
+
+    /// Caches GET responses with an `ETag` or `Last-Modified` and revalidates them with
+    /// conditional requests
+    ///
+    /// With [`HttpCache::fresh_responses`] it also serves responses within their
+    /// `Cache-Control: max-age` without a request. Clones of the cache share their
+    /// responses and statistics. See the [`cache`](crate::cache) module for details.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use servicestack::{HttpCache, JsonServiceClient};
+    ///
+    /// let mut client = JsonServiceClient::new("https://api.example.com");
+    /// client.set_http_cache(HttpCache::new().fresh_responses(true));
+    /// ```
+    pub fn set_http_cache(&mut self, cache: HttpCache) {
+        self.http_cache = Some(cache);
+    }
+
+    /// Removes the HTTP cache
+    pub fn clear_http_cache(&mut self) {
+        self.http_cache = None;
+    }
+
+    /// Returns the HTTP cache, if any
+    pub fn http_cache(&self) -> Option<&HttpCache> {
+        self.http_cache.as_ref()
+    }
+
+    /// Returns the hit and miss counts of the HTTP cache, if any
+    pub fn cache_stats(&self) -> Option<CacheStats> {
+        self.http_cache.as_ref().map(HttpCache::stats)
+    }
+
+    /// Adds a middleware to the client's middleware stack
+    ///
+    /// Middleware runs in registration order, outside the built-in bearer auth and
+    /// error mapping middleware. See the [`middleware`](crate::middleware) module for
+    /// the full ordering and an example.
+    pub fn add_middleware(&mut self, middleware: impl Middleware + 'static) {
+        self.middleware.push(Arc::new(middleware));
+    }
+
     /// Makes a GET request to the API
     ///
     /// # Arguments
@@ -99,7 +365,9 @@ impl JsonServiceClient {
     ///
     /// Returns the response DTO on success
     pub async fn get<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
-        self.send_request(request, "GET").await
+        self.send_request(request, HttpMethod::Get, RequestOptions::default())
+            .await
+            .map(ApiResponse::into_body)
     }
 
     /// Makes a POST request to the API
@@ -112,7 +380,9 @@ impl JsonServiceClient {
     ///
     /// Returns the response DTO on success
     pub async fn post<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
-        self.send_request(request, "POST").await
+        self.send_request(request, HttpMethod::Post, RequestOptions::default())
+            .await
+            .map(ApiResponse::into_body)
     }
 
     /// Makes a PUT request to the API
@@ -125,7 +395,9 @@ impl JsonServiceClient {
     ///
     /// Returns the response DTO on success
     pub async fn put<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
-        self.send_request(request, "PUT").await
+        self.send_request(request, HttpMethod::Put, RequestOptions::default())
+            .await
+            .map(ApiResponse::into_body)
     }
 
     /// Makes a DELETE request to the API
@@ -138,7 +410,9 @@ impl JsonServiceClient {
     ///
     /// Returns the response DTO on success
     pub async fn delete<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
-        self.send_request(request, "DELETE").await
+        self.send_request(request, HttpMethod::Delete, RequestOptions::default())
+            .await
+            .map(ApiResponse::into_body)
     }
 
     /// Makes a PATCH request to the API
@@ -151,7 +425,9 @@ impl JsonServiceClient {
     ///
     /// Returns the response DTO on success
     pub async fn patch<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
-        self.send_request(request, "PATCH").await
+        self.send_request(request, HttpMethod::Patch, RequestOptions::default())
+            .await
+            .map(ApiResponse::into_body)
     }
 
     /// Makes a request using the method specified in the request DTO
@@ -164,59 +440,342 @@ impl JsonServiceClient {
     ///
     /// Returns the response DTO on success
     pub async fn send<T: ServiceStackRequest>(&self, request: T) -> Result<T::Response> {
-        let method = request.method().as_str();
-        self.send_request(request, method).await
+        let method = request.method();
+        self.send_request(request, method, RequestOptions::default())
+            .await
+            .map(ApiResponse::into_body)
     }
 
-    /// Internal method to send a request
-    async fn send_request<T: ServiceStackRequest>(
+    /// Makes a request using the method specified in the request DTO, with per-call options
+    ///
+    /// The options add headers and query string parameters, override the timeout or
+    /// the bearer token for this call only.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use servicestack::{JsonServiceClient, RequestOptions, ServiceStackRequest};
+    /// # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, request: T) -> servicestack::Result<()> {
+    /// let options = RequestOptions::new()
+    ///     .header("X-Request-Id", "42")
+    ///     .bearer_token("other-token");
+    /// let response = client.send_with(request, options).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with<T: ServiceStackRequest>(
         &self,
         request: T,
-        method: &str,
+        options: RequestOptions,
     ) -> Result<T::Response> {
-        let path = request.path();
-        let url = format!("{}{}", self.base_url, path);
+        let method = request.method();
+        self.send_request(request, method, options)
+            .await
+            .map(ApiResponse::into_body)
+    }
 
-        let mut request_builder = match method.to_uppercase().as_str() {
-            "GET" => self.http_client.get(&url),
-            "POST" => self.http_client.post(&url),
-            "PUT" => self.http_client.put(&url),
-            "DELETE" => self.http_client.delete(&url),
-            "PATCH" => self.http_client.patch(&url),
-            _ => {
-                return Err(ServiceStackError::Other(format!(
-                    "Unsupported HTTP method: {}",
-                    method
-                )))
-            }
-        };
+    /// Sends several requests in one round trip using ServiceStack's auto-batched requests
+    ///
+    /// The DTOs are POSTed as a JSON array to the pre-defined route for
+    /// `{Operation}[]`, e.g. `/api/Hello[]` or `/json/reply/Hello[]`, and the responses are
+    /// returned in request order. If the service fails part way through, the error is a
+    /// [`ServiceStackError::BatchError`] with the index of the request that failed.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use servicestack::{JsonServiceClient, ServiceStackRequest};
+    /// # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, requests: Vec<T>) -> servicestack::Result<()> {
+    /// let responses = client.send_all(requests).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_all<T: ServiceStackRequest>(
+        &self,
+        requests: Vec<T>,
+    ) -> Result<Vec<T::Response>> {
+        if requests.is_empty() {
+            return Ok(Vec::new());
+        }
 
-        // Add bearer token if set
-        if let Some(token) = &self.bearer_token {
-            request_builder = request_builder.bearer_auth(token);
+        let count = requests.len();
+        let retryable = requests
+            .iter()
+            .all(|request| request.retryable() == Some(true));
+        let path = self
+            .predefined_routes
+            .path(&format!("{}[]", T::operation_name()));
+        let url = format!("{}{}", self.base_url, path);
+        let request_builder = self.http_client.post(&url).json(&requests);
+
+        // Capture how many requests completed, to report which one failed
+        let completed = batch::Completed::default();
+        let mut middleware = self.middleware_stack(retryable, true);
+        middleware.push(Arc::new(completed.clone()));
+        let response = self
+            .execute_with(request_builder, middleware, None)
+            .await
+            .map_err(|error| completed.batch_error(error))?;
+
+        // Void responses have no body to deserialize
+        if T::Response::IS_VOID {
+            return (0..count).map(|_| response::void()).collect();
         }
 
-        // For methods that support a body, add JSON body
-        if method.to_uppercase().as_str() != "GET" {
-            request_builder = request_builder.json(&request);
+        let responses: Vec<T::Response> = response::read_json(response).await?;
+        if responses.len() != count {
+            return Err(ServiceStackError::Other(format!(
+                "Expected {} batch responses, received {}",
+                count,
+                responses.len()
+            )));
         }
+        Ok(responses)
+    }
+
+    /// Makes a request using the method specified in the request DTO, returning an
+    /// [`ApiResult`] instead of an error
+    ///
+    /// Like the `api()` method of ServiceStack's clients, failures are returned as a
+    /// [`ResponseStatus`](crate::ResponseStatus), including transport failures such as
+    /// timeouts. See the [`api_result`](crate::api_result) module for an example.
+    pub async fn api<T: ServiceStackRequest>(&self, request: T) -> ApiResult<T::Response> {
+        self.send(request).await.into()
+    }
+
+    /// Like [`api`](Self::api), with per-call options
+    pub async fn api_with<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        options: RequestOptions,
+    ) -> ApiResult<T::Response> {
+        self.send_with(request, options).await.into()
+    }
 
-        // Send the request
-        let response = request_builder.send().await?;
+    /// Sends a request using the method specified in the request DTO and returns the
+    /// response as a [`Download`] without reading its body
+    ///
+    /// Downloads bypass the [`HttpCache`], if any, so their body is always streamed.
+    ///
+    /// See the [`download`](crate::download) module for an example.
+    pub async fn download<T: ServiceStackRequest>(&self, request: T) -> Result<Download> {
+        self.download_with(request, RequestOptions::default()).await
+    }
+
+    /// Like [`download`](Self::download), with per-call options
+    pub async fn download_with<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        options: RequestOptions,
+    ) -> Result<Download> {
+        let method = request.method();
+        let (request_builder, retryable, upload_progress) =
+            self.dto_request(request, method, options)?;
+        let middleware = self.middleware_stack(retryable, false);
+        let response = self
+            .execute_with(request_builder, middleware, upload_progress)
+            .await?;
+        Ok(Download::new(response))
+    }
 
-        // Check status code
+    /// Uploads a file with the request DTO as `multipart/form-data`, returning the
+    /// response DTO
+    ///
+    /// The equivalent of `PostFileWithRequest` in ServiceStack's clients. See the
+    /// [`upload`] module for an example.
+    pub async fn post_file_with_request<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        file: FilePart,
+    ) -> Result<T::Response> {
+        self.post_files_with_request(request, vec![file]).await
+    }
+
+    /// Uploads files with the request DTO as `multipart/form-data`, returning the
+    /// response DTO
+    ///
+    /// The DTO fields not used by the route are sent as form fields, followed by the
+    /// file parts.
+    pub async fn post_files_with_request<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        files: Vec<FilePart>,
+    ) -> Result<T::Response> {
+        self.post_files_with_request_with(request, files, RequestOptions::default())
+            .await
+    }
+
+    /// Like [`post_files_with_request`](Self::post_files_with_request), with per-call
+    /// options such as an upload progress callback
+    pub async fn post_files_with_request_with<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        files: Vec<FilePart>,
+        mut options: RequestOptions,
+    ) -> Result<T::Response> {
+        let (path, payload) = self.resolve_path(&request)?;
+        let mut url = format!("{}{}", self.base_url, path);
+        options.append_query(&mut url);
+        let form = upload::form(&payload, files).await?;
+
+        // Streamed file parts can't be replayed, so uploads are never retried
+        let upload_progress = options.take_upload_progress();
+        let request_builder = options.apply(self.http_client.post(&url))?.multipart(form);
+        let response = self
+            .execute(request_builder, false, upload_progress)
+            .await?;
+        response::read_body(response).await
+    }
+
+    /// Sends a streamed request body, returning the response DTO
+    ///
+    /// The body is sent with the method of the request DTO and the DTO fields not used
+    /// by the route are sent on the query string. See the [`body`](crate::body) module
+    /// for an example.
+    pub async fn send_body<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        body: RequestBody,
+    ) -> Result<T::Response> {
+        self.send_body_with(request, body, RequestOptions::default())
+            .await
+    }
+
+    /// Like [`send_body`](Self::send_body), with per-call options such as an upload
+    /// progress callback
+    pub async fn send_body_with<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        body: RequestBody,
+        mut options: RequestOptions,
+    ) -> Result<T::Response> {
+        let method = request.method();
+        let (path, payload) = self.resolve_path(&request)?;
+        let mut url = format!("{}{}", self.base_url, path);
+        query::append_query(&mut url, &query::to_query_string(&payload)?);
+        options.append_query(&mut url);
+
+        // Streamed bodies can't be replayed, so they are never retried
+        let upload_progress = options.take_upload_progress();
+        let request_builder = self
+            .http_client
+            .request(reqwest::Method::try_from(&method)?, &url);
+        let request_builder = body.apply(options.apply(request_builder)?)?;
+        let response = self
+            .execute(request_builder, false, upload_progress)
+            .await?;
+        response::read_body(response).await
+    }
+
+    /// Makes a HEAD request to the API, returning only the response status and headers
+    ///
+    /// The DTO fields are sent on the query string, as for GET requests.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use servicestack::{JsonServiceClient, ServiceStackRequest};
+    /// # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, request: T) -> servicestack::Result<()> {
+    /// let response = client.head(request).await?;
+    /// println!("{:?} {:?}", response.content_length(), response.header("ETag"));
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn head<T: ServiceStackRequest>(&self, request: T) -> Result<HeadResponse> {
+        let response = self
+            .send_dto(request, HttpMethod::Head, RequestOptions::default())
+            .await?;
+        Ok(HeadResponse::from_response(&response))
+    }
+
+    /// Makes a request using the method specified in the request DTO, returning the
+    /// response DTO with the status code, headers, final URL and elapsed time
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use servicestack::{JsonServiceClient, RequestOptions, ServiceStackRequest};
+    /// # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, request: T) -> servicestack::Result<()> {
+    /// let response = client
+    ///     .send_with_metadata(request, RequestOptions::default())
+    ///     .await?;
+    /// println!("{} in {:?}", response.status(), response.elapsed());
+    /// let total = response.header("X-Total-Count");
+    /// let dto = response.into_body();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_with_metadata<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        options: RequestOptions,
+    ) -> Result<ApiResponse<T::Response>> {
+        let method = request.method();
+        self.send_request(request, method, options).await
+    }
+
+    /// Internal method to send a request
+    async fn send_request<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        method: HttpMethod,
+        options: RequestOptions,
+    ) -> Result<ApiResponse<T::Response>> {
+        let started = Instant::now();
+        let response = self.send_dto(request, method, options).await?;
         let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(ServiceStackError::ApiError {
-                status: status.as_u16(),
-                message: error_text,
-            });
-        }
+        let headers = response.headers().clone();
+        let url = response.url().clone();
+
+        let body = response::read_body(response).await?;
+
+        Ok(ApiResponse::new(
+            body,
+            status,
+            headers,
+            url,
+            started.elapsed(),
+        ))
+    }
 
-        // Deserialize response
-        let response_dto = response.json::<T::Response>().await?;
-        Ok(response_dto)
+    /// Sends a request DTO through the middleware stack, returning the raw response
+    async fn send_dto<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        method: HttpMethod,
+        options: RequestOptions,
+    ) -> Result<reqwest::Response> {
+        let (request_builder, retryable, upload_progress) =
+            self.dto_request(request, method, options)?;
+        self.execute(request_builder, retryable, upload_progress)
+            .await
+    }
+
+    /// Builds the request for a request DTO, returning whether it may be retried and
+    /// its upload progress callback
+    fn dto_request<T: ServiceStackRequest>(
+        &self,
+        request: T,
+        method: HttpMethod,
+        mut options: RequestOptions,
+    ) -> Result<(reqwest::RequestBuilder, bool, Option<ProgressCallback>)> {
+        let retryable = request
+            .retryable()
+            .unwrap_or_else(|| method.is_idempotent());
+        let (mut url, body) =
+            route::resolve_url(&self.base_url, &request, &method, &self.predefined_routes)?;
+        options.append_query(&mut url);
+
+        let upload_progress = options.take_upload_progress();
+        let request_builder = self
+            .http_client
+            .request(reqwest::Method::try_from(&method)?, &url);
+        let request_builder = options.apply(request_builder)?;
+        let request_builder = match body {
+            Some(body) => request_builder.json(&body),
+            None => request_builder,
+        };
+        Ok((request_builder, retryable, upload_progress))
     }
 
     /// Makes a raw API request with custom serialization
@@ -227,11 +786,12 @@ impl JsonServiceClient {
     ///
     /// * `method` - The HTTP method (GET, POST, etc.)
     /// * `path` - The API endpoint path
-    /// * `body` - Optional request body (will be serialized to JSON)
+    /// * `body` - Optional request body (serialized to the query string for GET and
+    ///   DELETE requests, otherwise to a JSON body)
     ///
     /// # Returns
     ///
-    /// Returns the deserialized response
+    /// Returns the deserialized response; the body of a `()` response is not read
     pub async fn request<TRequest, TResponse>(
         &self,
         method: &str,
@@ -240,50 +800,102 @@ impl JsonServiceClient {
     ) -> Result<TResponse>
     where
         TRequest: Serialize,
-        TResponse: DeserializeOwned,
+        TResponse: DeserializeOwned + 'static,
     {
-        let url = format!("{}{}", self.base_url, path);
+        let method: HttpMethod = method.parse()?;
+        let (url, body) = route::request_url(&self.base_url, path, &method, body)?;
+
+        let request_builder = self
+            .http_client
+            .request(reqwest::Method::try_from(&method)?, &url);
+        let request_builder = match body {
+            Some(body) => request_builder.json(body),
+            None => request_builder,
+        };
+
+        // Send the request through the middleware stack
+        let response = self
+            .execute(request_builder, method.is_idempotent(), None)
+            .await?;
+
+        response::read_raw(response).await
+    }
 
-        let mut request_builder = match method.to_uppercase().as_str() {
-            "GET" => self.http_client.get(&url),
-            "POST" => self.http_client.post(&url),
-            "PUT" => self.http_client.put(&url),
-            "DELETE" => self.http_client.delete(&url),
-            "PATCH" => self.http_client.patch(&url),
-            _ => {
-                return Err(ServiceStackError::Other(format!(
-                    "Unsupported HTTP method: {}",
-                    method
-                )))
+    /// Builds a request and sends it through the middleware stack
+    ///
+    /// `retryable` controls whether the retry policy, if any, applies to the request, and
+    /// `upload_progress` is called as the request body is sent.
+    async fn execute(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+        retryable: bool,
+        upload_progress: Option<ProgressCallback>,
+    ) -> Result<reqwest::Response> {
+        let middleware = self.middleware_stack(retryable, true);
+        self.execute_with(request_builder, middleware, upload_progress)
+            .await
+    }
+
+    /// Builds a request and sends it through the given middleware stack
+    async fn execute_with(
+        &self,
+        request_builder: reqwest::RequestBuilder,
+        mut middleware: Vec<Arc<dyn Middleware>>,
+        upload_progress: Option<ProgressCallback>,
+    ) -> Result<reqwest::Response> {
+        let mut request = request_builder.build()?;
+
+        // reqwest only adds its default headers when sending, after the middleware ran
+        for (name, value) in &self.default_headers {
+            if let Entry::Vacant(entry) = request.headers_mut().entry(name) {
+                entry.insert(value.clone());
             }
-        };
+        }
+        if let Some(callback) = upload_progress {
+            middleware.push(Arc::new(UploadProgress::new(callback)));
+        }
+        Next::new(&self.http_client, &middleware).run(request).await
+    }
+
+    /// Returns the registered middleware followed by the built-in middleware
+    ///
+    /// The HTTP cache, if any, is only included when `cached` is true.
+    fn middleware_stack(&self, retryable: bool, cached: bool) -> Vec<Arc<dyn Middleware>> {
+        let mut stack = self.middleware.clone();
 
-        // Add bearer token if set
+        // Compress once, so retries resend the compressed body
+        if let Some(compression) = &self.request_compression {
+            stack.push(Arc::new(compression.clone()));
+        }
+        if let (Some(policy), true) = (&self.retry_policy, retryable) {
+            stack.push(Arc::new(Retry::new(policy.clone())));
+        }
+        if let Some(breaker) = &self.circuit_breaker {
+            stack.push(Arc::new(breaker.clone()));
+        }
         if let Some(token) = &self.bearer_token {
-            request_builder = request_builder.bearer_auth(token);
+            stack.push(Arc::new(BearerAuth::new(token.clone())));
         }
+        stack.push(Arc::new(ErrorMapping));
 
-        // Add body if provided
-        if let Some(body) = body {
-            request_builder = request_builder.json(body);
+        if !self.request_filters.is_empty() || !self.response_filters.is_empty() {
+            stack.push(Arc::new(Filters {
+                request_filters: self.request_filters.clone(),
+                response_filters: self.response_filters.clone(),
+            }));
         }
 
-        // Send the request
-        let response = request_builder.send().await?;
-
-        // Check status code
-        let status = response.status();
-        if !status.is_success() {
-            let error_text = response.text().await.unwrap_or_default();
-            return Err(ServiceStackError::ApiError {
-                status: status.as_u16(),
-                message: error_text,
-            });
+        // Innermost, so the cache sees the headers filters add, and inside the error
+        // mapping, so `304 Not Modified` can be answered from the cache
+        if let (Some(cache), true) = (&self.http_cache, cached) {
+            stack.push(Arc::new(cache.clone()));
         }
+        stack
+    }
 
-        // Deserialize response
-        let response_dto = response.json::<TResponse>().await?;
-        Ok(response_dto)
+    /// Resolves the request path and the DTO fields that remain to be sent
+    fn resolve_path<T: ServiceStackRequest>(&self, request: &T) -> Result<(String, Value)> {
+        route::resolve_request(request, &self.predefined_routes)
     }
 
     /// Returns the base URL of the client
@@ -300,7 +912,6 @@ impl JsonServiceClient {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::traits::ServiceStackResponse;
     use serde::Deserialize;
 
     #[derive(Serialize)]
@@ -346,4 +957,32 @@ mod tests {
         client.clear_bearer_token();
         assert!(client.bearer_token.is_none());
     }
+
+    #[test]
+    fn test_predefined_routes() {
+        let mut client = JsonServiceClient::new("https://api.example.com");
+        assert_eq!(client.predefined_routes(), &PredefinedRoutes::Api);
+        assert_eq!(client.predefined_routes().path("Hello"), "/api/Hello");
+
+        client.set_predefined_routes(PredefinedRoutes::JsonReply);
+        assert_eq!(
+            client.predefined_routes().path("Hello"),
+            "/json/reply/Hello"
+        );
+
+        let routes = PredefinedRoutes::BasePath("/v1/".to_string());
+        assert_eq!(routes.path("Hello"), "/v1/Hello");
+    }
+
+    #[test]
+    fn test_request_query_string() {
+        let request = TestRequest {
+            name: "Hello World".to_string(),
+        };
+        assert_eq!(
+            query::to_query_string(&request).unwrap(),
+            "name=Hello%20World"
+        );
+        assert_eq!(request.path(), "/test");
+    }
 }
@@ -0,0 +1,309 @@
+use super::{JsonServiceClient, PredefinedRoutes};
+use crate::cache::HttpCache;
+use crate::circuit_breaker::CircuitBreaker;
+use crate::compression::RequestCompression;
+use crate::error::{Result, ServiceStackError};
+use crate::filter::{RequestFilter, ResponseFilter};
+use crate::middleware::Middleware;
+use crate::retry::RetryPolicy;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use reqwest::{Certificate, Client, ClientBuilder, Proxy, Url};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// Builder for configuring a [`JsonServiceClient`]
+///
+/// Created with [`JsonServiceClient::builder`]. Invalid settings such as a malformed
+/// base URL or header are reported by [`build`](Self::build) rather than panicking.
+///
+/// # Example
+///
+/// ```
+/// use servicestack::JsonServiceClient;
+/// use std::time::Duration;
+///
+/// let client = JsonServiceClient::builder("https://api.example.com")
+///     .connect_timeout(Duration::from_secs(5))
+///     .read_timeout(Duration::from_secs(20))
+///     .timeout(Duration::from_secs(60))
+///     .build()
+///     .unwrap();
+/// ```
+pub struct JsonServiceClientBuilder {
+    base_url: String,
+    http: ClientBuilder,
+    headers: HeaderMap,
+    bearer_token: Option<String>,
+    predefined_routes: PredefinedRoutes,
+    middleware: Vec<Arc<dyn Middleware>>,
+    request_filters: Vec<Arc<dyn RequestFilter>>,
+    response_filters: Vec<Arc<dyn ResponseFilter>>,
+    retry_policy: Option<RetryPolicy>,
+    circuit_breaker: Option<CircuitBreaker>,
+    request_compression: Option<RequestCompression>,
+    http_cache: Option<HttpCache>,
+    error: Option<ServiceStackError>,
+}
+
+impl JsonServiceClientBuilder {
+    /// Creates a builder for the given base URL with a 30 second total timeout
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Self {
+            base_url: base_url.into(),
+            http: Client::builder().timeout(Duration::from_secs(30)),
+            headers: HeaderMap::new(),
+            bearer_token: None,
+            predefined_routes: PredefinedRoutes::default(),
+            middleware: Vec::new(),
+            request_filters: Vec::new(),
+            response_filters: Vec::new(),
+            retry_policy: None,
+            circuit_breaker: None,
+            request_compression: None,
+            http_cache: None,
+            error: None,
+        }
+    }
+
+    /// Sets the total timeout for a request, from connecting until the body is read
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.timeout(timeout);
+        self
+    }
+
+    /// Sets the timeout for establishing a connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.connect_timeout(timeout);
+        self
+    }
+
+    /// Sets the timeout for each read from the connection
+    pub fn read_timeout(mut self, timeout: Duration) -> Self {
+        self.http = self.http.read_timeout(timeout);
+        self
+    }
+
+    /// Sets the `User-Agent` header sent with every request
+    pub fn user_agent(mut self, user_agent: impl AsRef<str>) -> Self {
+        match HeaderValue::from_str(user_agent.as_ref()) {
+            Ok(value) => self.http = self.http.user_agent(value),
+            Err(e) => self.set_error(ServiceStackError::InvalidHeader(format!(
+                "User-Agent: {}",
+                e
+            ))),
+        }
+        self
+    }
+
+    /// Adds a header sent with every request
+    pub fn default_header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value.as_ref()),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.headers.append(name, value);
+            }
+            (Err(e), _) => {
+                self.set_error(ServiceStackError::InvalidHeader(format!("{}: {}", name, e)))
+            }
+            (_, Err(e)) => {
+                self.set_error(ServiceStackError::InvalidHeader(format!("{}: {}", name, e)))
+            }
+        }
+        self
+    }
+
+    /// Adds headers sent with every request
+    pub fn default_headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Sets a custom base path for the pre-defined routes, e.g. `/v1` for `/v1/{Operation}`
+    pub fn base_path(mut self, base_path: impl Into<String>) -> Self {
+        self.predefined_routes = PredefinedRoutes::BasePath(base_path.into());
+        self
+    }
+
+    /// Sets the pre-defined routes used for requests without a path or route templates
+    pub fn predefined_routes(mut self, routes: PredefinedRoutes) -> Self {
+        self.predefined_routes = routes;
+        self
+    }
+
+    /// Sets the Bearer token for authentication
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Adds a middleware to the client's middleware stack, in registration order
+    pub fn middleware(mut self, middleware: impl Middleware + 'static) -> Self {
+        self.middleware.push(Arc::new(middleware));
+        self
+    }
+
+    /// Adds a filter that can inspect and change every outgoing request
+    pub fn request_filter(mut self, filter: impl RequestFilter + 'static) -> Self {
+        self.request_filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Adds a filter that can inspect every raw response before deserialization
+    pub fn response_filter(mut self, filter: impl ResponseFilter + 'static) -> Self {
+        self.response_filters.push(Arc::new(filter));
+        self
+    }
+
+    /// Sets the policy for retrying transient failures of retryable requests
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Sets a circuit breaker that fails requests fast while the service is unavailable
+    pub fn circuit_breaker(mut self, breaker: CircuitBreaker) -> Self {
+        self.circuit_breaker = Some(breaker);
+        self
+    }
+
+    /// Compresses request bodies at or above a minimum size
+    pub fn request_compression(mut self, compression: RequestCompression) -> Self {
+        self.request_compression = Some(compression);
+        self
+    }
+
+    /// Caches GET responses and revalidates them with conditional requests
+    pub fn http_cache(mut self, cache: HttpCache) -> Self {
+        self.http_cache = Some(cache);
+        self
+    }
+
+    /// Routes requests through a proxy
+    pub fn proxy(mut self, proxy: Proxy) -> Self {
+        self.http = self.http.proxy(proxy);
+        self
+    }
+
+    /// Disables proxies, including system proxies from the environment
+    pub fn no_proxy(mut self) -> Self {
+        self.http = self.http.no_proxy();
+        self
+    }
+
+    /// Adds a trusted root certificate, e.g. for a private certificate authority
+    pub fn add_root_certificate(mut self, certificate: Certificate) -> Self {
+        self.http = self.http.add_root_certificate(certificate);
+        self
+    }
+
+    /// Controls whether the built-in root certificates are trusted (defaults to true)
+    pub fn tls_built_in_root_certs(mut self, enabled: bool) -> Self {
+        self.http = self.http.tls_built_in_root_certs(enabled);
+        self
+    }
+
+    /// Accepts invalid TLS certificates
+    ///
+    /// # Warning
+    ///
+    /// This makes the connection vulnerable to man-in-the-middle attacks and should
+    /// only be used against local development servers.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.http = self.http.danger_accept_invalid_certs(accept);
+        self
+    }
+
+    /// Only allows requests over HTTPS
+    pub fn https_only(mut self, enabled: bool) -> Self {
+        self.http = self.http.https_only(enabled);
+        self
+    }
+
+    /// Builds the client
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the base URL or a header is invalid, or the underlying
+    /// HTTP client cannot be created.
+    pub fn build(self) -> Result<JsonServiceClient> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let base_url = self.base_url.trim_end_matches('/').to_string();
+        Url::parse(&base_url)
+            .map_err(|e| ServiceStackError::InvalidUrl(format!("{}: {}", base_url, e)))?;
+
+        let default_headers = self.headers.clone();
+        let http_client = self.http.default_headers(self.headers).build()?;
+
+        Ok(JsonServiceClient {
+            base_url,
+            http_client,
+            default_headers,
+            bearer_token: self.bearer_token,
+            predefined_routes: self.predefined_routes,
+            middleware: self.middleware,
+            request_filters: self.request_filters,
+            response_filters: self.response_filters,
+            retry_policy: self.retry_policy,
+            circuit_breaker: self.circuit_breaker,
+            request_compression: self.request_compression,
+            http_cache: self.http_cache,
+        })
+    }
+
+    /// Records the first configuration error, reported by `build`
+    fn set_error(&mut self, error: ServiceStackError) {
+        self.error.get_or_insert(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_build_with_options() {
+        let client = JsonServiceClientBuilder::new("https://api.example.com/")
+            .timeout(Duration::from_secs(5))
+            .connect_timeout(Duration::from_secs(1))
+            .read_timeout(Duration::from_secs(2))
+            .user_agent("test-agent/1.0")
+            .default_header("X-Tenant", "acme")
+            .bearer_token("token")
+            .base_path("/v1")
+            .no_proxy()
+            .build()
+            .unwrap();
+
+        assert_eq!(client.base_url(), "https://api.example.com");
+        assert_eq!(client.bearer_token.as_deref(), Some("token"));
+        assert_eq!(
+            client.predefined_routes(),
+            &PredefinedRoutes::BasePath("/v1".to_string())
+        );
+    }
+
+    #[test]
+    fn test_invalid_base_url() {
+        let result = JsonServiceClientBuilder::new("not a url").build();
+        assert!(matches!(result, Err(ServiceStackError::InvalidUrl(_))));
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let result = JsonServiceClientBuilder::new("https://api.example.com")
+            .default_header("Bad Header", "value")
+            .build();
+        assert!(matches!(result, Err(ServiceStackError::InvalidHeader(_))));
+
+        let result = JsonServiceClientBuilder::new("https://api.example.com")
+            .user_agent("bad\nagent")
+            .build();
+        assert!(matches!(result, Err(ServiceStackError::InvalidHeader(_))));
+    }
+}
@@ -0,0 +1,103 @@
+use super::JsonServiceClient;
+use crate::error::Result;
+use crate::traits::ServiceStackRequest;
+use futures_util::stream::{self, Stream, StreamExt};
+
+impl JsonServiceClient {
+    /// Sends requests concurrently with at most `max_in_flight` in flight at a time,
+    /// returning the responses in input order
+    ///
+    /// Fails fast: the first error is returned, no further requests are started and
+    /// requests still in flight are cancelled.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use servicestack::{JsonServiceClient, ServiceStackRequest};
+    /// # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, requests: Vec<T>) -> servicestack::Result<()> {
+    /// let responses = client.send_concurrent(requests, 8).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn send_concurrent<T, I>(
+        &self,
+        requests: I,
+        max_in_flight: usize,
+    ) -> Result<Vec<T::Response>>
+    where
+        T: ServiceStackRequest,
+        I: IntoIterator<Item = T>,
+    {
+        let mut results = Vec::new();
+        let mut responses = std::pin::pin!(self.send_concurrent_stream(requests, max_in_flight));
+        while let Some((index, result)) = responses.next().await {
+            insert_at(&mut results, index, result?);
+        }
+        Ok(results.into_iter().flatten().collect())
+    }
+
+    /// Sends requests concurrently with at most `max_in_flight` in flight at a time,
+    /// returning every result in input order
+    ///
+    /// Unlike [`send_concurrent`](Self::send_concurrent), a failed request does not stop
+    /// the others.
+    pub async fn send_concurrent_all<T, I>(
+        &self,
+        requests: I,
+        max_in_flight: usize,
+    ) -> Vec<Result<T::Response>>
+    where
+        T: ServiceStackRequest,
+        I: IntoIterator<Item = T>,
+    {
+        let mut results = Vec::new();
+        let mut responses = std::pin::pin!(self.send_concurrent_stream(requests, max_in_flight));
+        while let Some((index, result)) = responses.next().await {
+            insert_at(&mut results, index, result);
+        }
+        results.into_iter().flatten().collect()
+    }
+
+    /// Sends requests concurrently with at most `max_in_flight` in flight at a time,
+    /// yielding `(index, result)` pairs as they complete
+    ///
+    /// `index` is the position of the request in `requests`. Requests are started
+    /// lazily as the stream is polled, and dropping the stream cancels the requests
+    /// still in flight, so stopping at the first error fails fast.
+    ///
+    /// # Example
+    ///
+    /// ```no_run
+    /// # use servicestack::{JsonServiceClient, ServiceStackRequest};
+    /// use futures_util::StreamExt;
+    ///
+    /// # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, requests: Vec<T>) {
+    /// let mut responses = std::pin::pin!(client.send_concurrent_stream(requests, 8));
+    /// while let Some((index, result)) = responses.next().await {
+    ///     println!("request {} succeeded: {}", index, result.is_ok());
+    /// }
+    /// # }
+    /// ```
+    pub fn send_concurrent_stream<'a, T, I>(
+        &'a self,
+        requests: I,
+        max_in_flight: usize,
+    ) -> impl Stream<Item = (usize, Result<T::Response>)> + 'a
+    where
+        T: ServiceStackRequest + 'a,
+        I: IntoIterator<Item = T>,
+        I::IntoIter: 'a,
+    {
+        stream::iter(requests.into_iter().enumerate())
+            .map(move |(index, request)| async move { (index, self.send(request).await) })
+            .buffer_unordered(max_in_flight.max(1))
+    }
+}
+
+/// Stores a result at its input position, growing the list as needed
+fn insert_at<T>(results: &mut Vec<Option<T>>, index: usize, value: T) {
+    if results.len() <= index {
+        results.resize_with(index + 1, || None);
+    }
+    results[index] = Some(value);
+}
@@ -0,0 +1,243 @@
+//! Request body compression
+//!
+//! A [`RequestCompression`] set on a [`JsonServiceClient`](crate::JsonServiceClient)
+//! compresses request bodies at or above a minimum size and sends them with a
+//! `Content-Encoding` header, which ServiceStack decompresses before deserializing the
+//! request. Smaller bodies, bodies that already have a `Content-Encoding` and streamed
+//! bodies are sent as-is.
+//!
+//! Gzip and deflate are always available; brotli and zstd need the `brotli` and `zstd`
+//! cargo features. The `gzip`, `deflate`, `brotli` and `zstd` features also turn on
+//! decompression of responses in that encoding, advertised with `Accept-Encoding`.
+//!
+//! # Example
+//!
+//! ```
+//! use servicestack::{Compression, JsonServiceClient, RequestCompression};
+//!
+//! let client = JsonServiceClient::builder("https://api.example.com")
+//!     .request_compression(RequestCompression::new(Compression::Gzip).min_size(4096))
+//!     .build()
+//!     .unwrap();
+//! ```
+
+use crate::error::Result;
+use crate::middleware::{Middleware, Next};
+use async_trait::async_trait;
+use flate2::write::{GzEncoder, ZlibEncoder};
+use reqwest::header::{HeaderValue, CONTENT_ENCODING, CONTENT_LENGTH};
+use std::fmt;
+use std::io::{self, Write};
+
+/// A compression algorithm for request bodies
+///
+/// Non-exhaustive because the `Brotli` and `Zstd` variants only exist when their cargo
+/// features are enabled, and any crate in the dependency graph can enable them.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum Compression {
+    /// `Content-Encoding: gzip`
+    Gzip,
+    /// `Content-Encoding: deflate` (zlib format)
+    Deflate,
+    /// `Content-Encoding: br`
+    #[cfg(feature = "brotli")]
+    Brotli,
+    /// `Content-Encoding: zstd`
+    #[cfg(feature = "zstd")]
+    Zstd,
+}
+
+impl Compression {
+    /// Returns the `Content-Encoding` token of the algorithm
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Compression::Gzip => "gzip",
+            Compression::Deflate => "deflate",
+            #[cfg(feature = "brotli")]
+            Compression::Brotli => "br",
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => "zstd",
+        }
+    }
+
+    /// Compresses bytes with the algorithm
+    pub fn compress(&self, bytes: &[u8]) -> io::Result<Vec<u8>> {
+        match self {
+            Compression::Gzip => {
+                let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            Compression::Deflate => {
+                let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(bytes)?;
+                encoder.finish()
+            }
+            #[cfg(feature = "brotli")]
+            Compression::Brotli => {
+                let mut compressed = Vec::new();
+                {
+                    let mut encoder = brotli::CompressorWriter::new(&mut compressed, 4096, 5, 22);
+                    encoder.write_all(bytes)?;
+                }
+                Ok(compressed)
+            }
+            #[cfg(feature = "zstd")]
+            Compression::Zstd => zstd::encode_all(bytes, 0),
+        }
+    }
+}
+
+impl fmt::Display for Compression {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Compresses request bodies at or above a minimum size
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestCompression {
+    algorithm: Compression,
+    min_size: usize,
+}
+
+impl RequestCompression {
+    /// Compresses bodies of 1 KiB or more with the given algorithm
+    pub fn new(algorithm: Compression) -> Self {
+        Self {
+            algorithm,
+            min_size: 1024,
+        }
+    }
+
+    /// Sets the minimum body size in bytes to compress
+    pub fn min_size(mut self, min_size: usize) -> Self {
+        self.min_size = min_size;
+        self
+    }
+
+    /// Returns the compression algorithm
+    pub fn algorithm(&self) -> Compression {
+        self.algorithm
+    }
+
+    /// Compresses the body of a request, if it should be
+    fn compress(&self, request: &mut reqwest::Request) -> Result<()> {
+        if request.headers().contains_key(CONTENT_ENCODING) {
+            return Ok(());
+        }
+        let Some(bytes) = request.body().and_then(|body| body.as_bytes()) else {
+            return Ok(());
+        };
+        if bytes.len() < self.min_size {
+            return Ok(());
+        }
+
+        let compressed = self.algorithm.compress(bytes)?;
+        let headers = request.headers_mut();
+        headers.insert(
+            CONTENT_ENCODING,
+            HeaderValue::from_static(self.algorithm.as_str()),
+        );
+        headers.insert(CONTENT_LENGTH, HeaderValue::from(compressed.len()));
+        *request.body_mut() = Some(compressed.into());
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl Middleware for RequestCompression {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response> {
+        self.compress(&mut request)?;
+        next.run(request).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use flate2::read::{GzDecoder, ZlibDecoder};
+    use std::io::Read;
+
+    fn post(body: &str) -> reqwest::Request {
+        reqwest::Client::new()
+            .post("https://api.example.com/hello")
+            .body(body.to_string())
+            .build()
+            .unwrap()
+    }
+
+    #[test]
+    fn test_compresses_bodies_at_min_size() {
+        let body = "a".repeat(100);
+        let mut request = post(&body);
+        RequestCompression::new(Compression::Gzip)
+            .min_size(100)
+            .compress(&mut request)
+            .unwrap();
+
+        assert_eq!(request.headers()[CONTENT_ENCODING], "gzip");
+        let compressed = request.body().unwrap().as_bytes().unwrap();
+        assert_eq!(
+            request.headers()[CONTENT_LENGTH],
+            compressed.len().to_string().as_str()
+        );
+        let mut decompressed = String::new();
+        GzDecoder::new(compressed)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, body);
+    }
+
+    #[test]
+    fn test_skips_small_and_encoded_bodies() {
+        let compression = RequestCompression::new(Compression::Deflate).min_size(100);
+
+        let mut request = post("small");
+        compression.compress(&mut request).unwrap();
+        assert!(!request.headers().contains_key(CONTENT_ENCODING));
+        assert_eq!(request.body().unwrap().as_bytes(), Some(&b"small"[..]));
+
+        let body = "a".repeat(100);
+        let mut request = post(&body);
+        request
+            .headers_mut()
+            .insert(CONTENT_ENCODING, HeaderValue::from_static("identity"));
+        compression.compress(&mut request).unwrap();
+        assert_eq!(request.headers()[CONTENT_ENCODING], "identity");
+        assert_eq!(request.body().unwrap().as_bytes(), Some(body.as_bytes()));
+    }
+
+    #[test]
+    fn test_deflate_uses_zlib_format() {
+        let compressed = Compression::Deflate.compress(b"hello deflate").unwrap();
+        let mut decompressed = String::new();
+        ZlibDecoder::new(&compressed[..])
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, "hello deflate");
+    }
+
+    #[cfg(feature = "brotli")]
+    #[test]
+    fn test_brotli_round_trip() {
+        let compressed = Compression::Brotli.compress(b"hello brotli").unwrap();
+        let mut decompressed = String::new();
+        brotli::Decompressor::new(&compressed[..], 4096)
+            .read_to_string(&mut decompressed)
+            .unwrap();
+        assert_eq!(decompressed, "hello brotli");
+    }
+
+    #[cfg(feature = "zstd")]
+    #[test]
+    fn test_zstd_round_trip() {
+        let compressed = Compression::Zstd.compress(b"hello zstd").unwrap();
+        assert_eq!(zstd::decode_all(&compressed[..]).unwrap(), b"hello zstd");
+    }
+}
@@ -0,0 +1,191 @@
+//! Streaming downloads
+//!
+//! [`JsonServiceClient::download`](crate::JsonServiceClient::download) sends a request and
+//! returns a [`Download`] before the body is read, so files and `Stream` responses can be
+//! consumed as a stream of chunks or written to a file without buffering them in memory.
+//!
+//! The client's total timeout also covers reading the body; override it for large
+//! downloads with [`RequestOptions::timeout`](crate::RequestOptions::timeout).
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use servicestack::{JsonServiceClient, ServiceStackRequest};
+//! # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, request: T) -> servicestack::Result<()> {
+//! let download = client.download(request).await?;
+//! let file_name = download.file_name().unwrap_or_else(|| "download.bin".to_string());
+//! let bytes = download
+//!     .on_progress(|progress| println!("{:?}", progress.fraction()))
+//!     .save_to(&file_name)
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::Result;
+use crate::progress::{Progress, ProgressCallback};
+use bytes::Bytes;
+use futures_util::{Stream, StreamExt};
+use percent_encoding::percent_decode_str;
+use reqwest::header::{HeaderMap, CONTENT_DISPOSITION, CONTENT_TYPE};
+use std::fmt;
+use std::path::Path;
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+/// A response whose body has not been read yet
+pub struct Download {
+    response: reqwest::Response,
+    progress: Option<ProgressCallback>,
+}
+
+impl fmt::Debug for Download {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Download")
+            .field("status", &self.response.status())
+            .field("content_type", &self.content_type())
+            .field("content_length", &self.content_length())
+            .field("file_name", &self.file_name())
+            .finish()
+    }
+}
+
+impl Download {
+    pub(crate) fn new(response: reqwest::Response) -> Self {
+        Self {
+            response,
+            progress: None,
+        }
+    }
+
+    /// Returns the HTTP status code
+    pub fn status(&self) -> u16 {
+        self.response.status().as_u16()
+    }
+
+    /// Returns the response headers
+    pub fn headers(&self) -> &HeaderMap {
+        self.response.headers()
+    }
+
+    /// Returns the `Content-Type` header
+    pub fn content_type(&self) -> Option<&str> {
+        self.response.headers().get(CONTENT_TYPE)?.to_str().ok()
+    }
+
+    /// Returns the length of the body, when the service sent a `Content-Length`
+    pub fn content_length(&self) -> Option<u64> {
+        self.response.content_length()
+    }
+
+    /// Returns the file name from the `Content-Disposition` header, if any
+    pub fn file_name(&self) -> Option<String> {
+        let value = self.response.headers().get(CONTENT_DISPOSITION)?;
+        content_disposition_file_name(value.to_str().ok()?)
+    }
+
+    /// Sets a callback invoked after each chunk of the body is received
+    pub fn on_progress(mut self, callback: impl FnMut(Progress) + Send + Sync + 'static) -> Self {
+        self.progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Returns the body as a stream of chunks
+    pub fn bytes_stream(self) -> impl Stream<Item = Result<Bytes>> + Send {
+        let total = self.content_length();
+        let mut progress = self.progress;
+        let mut transferred = 0;
+        self.response.bytes_stream().map(move |chunk| {
+            let chunk = chunk?;
+            transferred += chunk.len() as u64;
+            if let Some(callback) = &mut progress {
+                callback(Progress { transferred, total });
+            }
+            Ok(chunk)
+        })
+    }
+
+    /// Writes the body to a writer, returning the number of bytes written
+    pub async fn write_to<W>(self, writer: &mut W) -> Result<u64>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let mut written = 0;
+        let mut chunks = std::pin::pin!(self.bytes_stream());
+        while let Some(chunk) = chunks.next().await {
+            let chunk = chunk?;
+            writer.write_all(&chunk).await?;
+            written += chunk.len() as u64;
+        }
+        writer.flush().await?;
+        Ok(written)
+    }
+
+    /// Writes the body to a file, returning the number of bytes written
+    ///
+    /// The file is created or truncated, and removed again if the download fails.
+    pub async fn save_to(self, path: impl AsRef<Path>) -> Result<u64> {
+        let path = path.as_ref();
+        let mut file = tokio::fs::File::create(path).await?;
+        let result = self.write_to(&mut file).await;
+        if result.is_err() {
+            drop(file);
+            let _ = tokio::fs::remove_file(path).await;
+        }
+        result
+    }
+}
+
+/// Extracts the file name from a `Content-Disposition` header value
+///
+/// Prefers the percent-encoded RFC 6266 `filename*` parameter over `filename`.
+fn content_disposition_file_name(value: &str) -> Option<String> {
+    let mut file_name = None;
+    for param in value.split(';').skip(1) {
+        let Some((name, value)) = param.split_once('=') else {
+            continue;
+        };
+        let value = value.trim();
+        match name.trim().to_ascii_lowercase().as_str() {
+            "filename*" => {
+                // charset'language'percent-encoded-name
+                let decoded = value
+                    .splitn(3, '\'')
+                    .nth(2)
+                    .and_then(|encoded| percent_decode_str(encoded).decode_utf8().ok());
+                if let Some(decoded) = decoded {
+                    return Some(decoded.into_owned());
+                }
+            }
+            "filename" => {
+                file_name.get_or_insert_with(|| value.trim_matches('"').to_string());
+            }
+            _ => {}
+        }
+    }
+    file_name
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_content_disposition_file_name() {
+        assert_eq!(
+            content_disposition_file_name(r#"attachment; filename="report 2024.pdf""#).as_deref(),
+            Some("report 2024.pdf")
+        );
+        assert_eq!(
+            content_disposition_file_name("inline; filename=photo.jpg").as_deref(),
+            Some("photo.jpg")
+        );
+        assert_eq!(
+            content_disposition_file_name(
+                "attachment; filename=\"naive.txt\"; filename*=UTF-8''na%C3%AFve.txt"
+            )
+            .as_deref(),
+            Some("na\u{ef}ve.txt")
+        );
+        assert_eq!(content_disposition_file_name("attachment"), None);
+    }
+}
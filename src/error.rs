@@ -1,10 +1,15 @@
-use thiserror::Error;
+//! Error types for ServiceStack client
+
+use crate::response_status::{ResponseError, ResponseStatus};
 
-/// Result type for ServiceStack operations
+/// Result type alias for ServiceStack operations
 pub type Result<T> = std::result::Result<T, ServiceStackError>;
 
+/// Alias for [`ServiceStackError`]
+pub type Error = ServiceStackError;
+
 /// Error types that can occur when using ServiceStack client
-#[derive(Error, Debug)]
+#[derive(thiserror::Error, Debug)]
 pub enum ServiceStackError {
     /// HTTP request error
     #[error("HTTP request failed: {0}")]
@@ -15,8 +20,46 @@ pub enum ServiceStackError {
     JsonError(#[from] serde_json::Error),
 
     /// API returned an error response
+    ///
+    /// `message` is the ServiceStack error message when the body contains a
+    /// `ResponseStatus`, otherwise the raw response body.
     #[error("API error: {status} - {message}")]
-    ApiError { status: u16, message: String },
+    ApiError {
+        status: u16,
+        message: String,
+        response_status: Option<Box<ResponseStatus>>,
+    },
+
+    /// An auto-batched request failed
+    ///
+    /// `index` is the position of the request that failed, when the service reports
+    /// how many requests completed before it.
+    #[error(
+        "Batch request failed at item {}: {source}",
+        .index.map_or_else(|| "unknown".to_string(), |index| index.to_string())
+    )]
+    BatchError {
+        index: Option<usize>,
+        source: Box<ServiceStackError>,
+    },
+
+    /// I/O error while reading or writing a streamed body
+    #[error("I/O error: {0}")]
+    IoError(#[from] std::io::Error),
+
+    /// A route template placeholder has no value on the request DTO
+    #[error("Missing value for route parameter '{name}' in route '{route}'")]
+    MissingRouteParameter { route: String, name: String },
+
+    /// The circuit breaker is open, so the request was not sent
+    ///
+    /// `retry_after` is the time left until the circuit lets a probe request through.
+    #[error("Circuit breaker is open, retry after {retry_after:?}")]
+    CircuitOpen { retry_after: std::time::Duration },
+
+    /// Invalid HTTP header name or value
+    #[error("Invalid header: {0}")]
+    InvalidHeader(String),
 
     /// Invalid URL
     #[error("Invalid URL: {0}")]
@@ -25,32 +68,195 @@ pub enum ServiceStackError {
     /// Other errors
     #[error("Error: {0}")]
     Other(String),
-//! Error types for ServiceStack client
+}
 
-use thiserror::Error;
+impl ServiceStackError {
+    /// Create a new error with a custom message
+    pub fn message<S: Into<String>>(msg: S) -> Self {
+        ServiceStackError::Other(msg.into())
+    }
 
-/// Result type alias for ServiceStack operations
-pub type Result<T> = std::result::Result<T, Error>;
+    /// Creates an `ApiError` from an error response, parsing its `ResponseStatus` if present
+    pub fn from_response_body(status: u16, body: String) -> Self {
+        let response_status = ResponseStatus::from_error_body(&body);
+        let message = response_status
+            .as_ref()
+            .and_then(|rs| rs.message.clone().or_else(|| rs.error_code.clone()))
+            .filter(|message| !message.is_empty())
+            .unwrap_or(body);
+        ServiceStackError::ApiError {
+            status,
+            message,
+            response_status: response_status.map(Box::new),
+        }
+    }
 
-/// Error types that can occur when using the ServiceStack client
-#[derive(Error, Debug)]
-pub enum Error {
-    /// HTTP request error
-    #[error("HTTP request failed: {0}")]
-    Request(#[from] reqwest::Error),
+    /// Returns the HTTP status code of an `ApiError`
+    pub fn status_code(&self) -> Option<u16> {
+        match self {
+            ServiceStackError::ApiError { status, .. } => Some(*status),
+            ServiceStackError::BatchError { source, .. } => source.status_code(),
+            _ => None,
+        }
+    }
 
-    /// JSON serialization/deserialization error
-    #[error("JSON error: {0}")]
-    Json(#[from] serde_json::Error),
+    /// Returns the `ResponseStatus` returned by the service, if any
+    pub fn response_status(&self) -> Option<&ResponseStatus> {
+        match self {
+            ServiceStackError::ApiError {
+                response_status, ..
+            } => response_status.as_deref(),
+            ServiceStackError::BatchError { source, .. } => source.response_status(),
+            _ => None,
+        }
+    }
 
-    /// Generic error with custom message
-    #[error("{0}")]
-    Message(String),
+    /// Returns the ServiceStack error code, if any
+    pub fn error_code(&self) -> Option<&str> {
+        self.response_status()?.error_code.as_deref()
+    }
+
+    /// Returns whether the service rejected the request with field validation errors
+    pub fn is_validation_error(&self) -> bool {
+        self.response_status()
+            .is_some_and(ResponseStatus::is_validation_error)
+    }
+
+    /// Returns the validation errors for a field, matching the field name case-insensitively
+    pub fn field_errors(&self, field_name: &str) -> Vec<&ResponseError> {
+        self.response_status()
+            .map(|status| status.field_errors(field_name))
+            .unwrap_or_default()
+    }
+
+    /// Returns the first validation error message for a field, if any
+    pub fn field_error_message(&self, field_name: &str) -> Option<&str> {
+        self.response_status()?
+            .field_error(field_name)?
+            .message
+            .as_deref()
+    }
+
+    /// Returns all field-level errors returned by the service
+    pub fn errors(&self) -> impl Iterator<Item = &ResponseError> {
+        self.response_status()
+            .into_iter()
+            .flat_map(|status| status.errors.iter())
+    }
+
+    /// Converts the error into a `ResponseStatus`
+    ///
+    /// Returns the service's `ResponseStatus` when it sent one; otherwise a synthetic
+    /// status is created with an error code naming the kind of failure (e.g.
+    /// `NotFound`, `Timeout` or `ConnectionError`) and the error's message.
+    pub fn to_response_status(&self) -> ResponseStatus {
+        if let Some(status) = self.response_status() {
+            return status.clone();
+        }
+
+        let (error_code, message) = match self {
+            ServiceStackError::ApiError {
+                status, message, ..
+            } => {
+                let reason = reqwest::StatusCode::from_u16(*status)
+                    .ok()
+                    .and_then(|status| status.canonical_reason())
+                    .unwrap_or("HttpError");
+                let error_code: String =
+                    reason.chars().filter(char::is_ascii_alphanumeric).collect();
+                let message = if message.is_empty() {
+                    reason.to_string()
+                } else {
+                    message.clone()
+                };
+                return ResponseStatus {
+                    error_code: Some(error_code),
+                    message: Some(message),
+                    ..Default::default()
+                };
+            }
+            ServiceStackError::BatchError { source, .. } => return source.to_response_status(),
+            ServiceStackError::RequestError(e) if e.is_timeout() => ("Timeout", self.to_string()),
+            ServiceStackError::RequestError(e) if e.is_connect() => {
+                ("ConnectionError", self.to_string())
+            }
+            ServiceStackError::RequestError(_) => ("RequestError", self.to_string()),
+            ServiceStackError::JsonError(_) => ("SerializationError", self.to_string()),
+            ServiceStackError::MissingRouteParameter { .. } => {
+                ("MissingRouteParameter", self.to_string())
+            }
+            ServiceStackError::CircuitOpen { .. } => ("CircuitOpen", self.to_string()),
+            ServiceStackError::IoError(_) => ("IoError", self.to_string()),
+            ServiceStackError::InvalidHeader(_) => ("InvalidHeader", self.to_string()),
+            ServiceStackError::InvalidUrl(_) => ("InvalidUrl", self.to_string()),
+            ServiceStackError::Other(_) => ("Exception", self.to_string()),
+        };
+        ResponseStatus {
+            error_code: Some(error_code.to_string()),
+            message: Some(message),
+            ..Default::default()
+        }
+    }
+
+    /// Returns a message summarizing the error, suitable for display
+    ///
+    /// Uses the `ResponseStatus` summary when the service returned one,
+    /// otherwise the error's display message.
+    pub fn summary_message(&self) -> String {
+        match self
+            .response_status()
+            .and_then(ResponseStatus::summary_message)
+        {
+            Some(message) => message.to_string(),
+            None => match self {
+                ServiceStackError::ApiError { message, .. } => message.clone(),
+                ServiceStackError::BatchError { source, .. } => source.summary_message(),
+                _ => self.to_string(),
+            },
+        }
+    }
 }
 
-impl Error {
-    /// Create a new error with a custom message
-    pub fn message<S: Into<String>>(msg: S) -> Self {
-        Error::Message(msg.into())
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_response_body_with_response_status() {
+        let body = r#"{"responseStatus":{"errorCode":"NotFound","message":"User not found"}}"#;
+        let error = ServiceStackError::from_response_body(404, body.to_string());
+        assert_eq!(error.to_string(), "API error: 404 - User not found");
+        assert_eq!(error.status_code(), Some(404));
+        assert_eq!(error.error_code(), Some("NotFound"));
+        assert_eq!(
+            error.response_status().unwrap().message.as_deref(),
+            Some("User not found")
+        );
+    }
+
+    #[test]
+    fn test_validation_error_accessors() {
+        let body = r#"{"responseStatus":{"errorCode":"NotEmpty","message":"'Name' must not be empty.","errors":[
+            {"errorCode":"NotEmpty","fieldName":"Name","message":"'Name' must not be empty."},
+            {"errorCode":"Email","fieldName":"Email","message":"Invalid email"}]}}"#;
+        let error = ServiceStackError::from_response_body(400, body.to_string());
+        assert!(error.is_validation_error());
+        assert_eq!(error.field_errors("name").len(), 1);
+        assert_eq!(error.field_error_message("EMAIL"), Some("Invalid email"));
+        assert!(error.field_error_message("Age").is_none());
+        assert_eq!(error.errors().count(), 2);
+        assert_eq!(error.summary_message(), "'Name' must not be empty.");
+    }
+
+    #[test]
+    fn test_from_response_body_falls_back_to_raw_text() {
+        let error = ServiceStackError::from_response_body(502, "Bad Gateway".to_string());
+        assert_eq!(error.to_string(), "API error: 502 - Bad Gateway");
+        assert!(error.response_status().is_none());
+        assert!(error.error_code().is_none());
+        assert!(!error.is_validation_error());
+        assert!(error.field_errors("Name").is_empty());
+        assert_eq!(error.errors().count(), 0);
+        assert_eq!(error.summary_message(), "Bad Gateway");
     }
 }
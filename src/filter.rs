@@ -0,0 +1,67 @@
+//! Global request and response filters
+//!
+//! Filters run on every request sent by a [`JsonServiceClient`](crate::JsonServiceClient),
+//! like the `RequestFilter`/`ResponseFilter` callbacks of ServiceStack's .NET clients.
+//! Closures are accepted as synchronous filters; implement the traits directly for
+//! asynchronous filters.
+//!
+//! # Example
+//!
+//! ```
+//! use reqwest::header::HeaderValue;
+//! use servicestack::JsonServiceClient;
+//!
+//! let mut client = JsonServiceClient::new("https://api.example.com");
+//! client.add_request_filter(|request: &mut reqwest::Request| {
+//!     request
+//!         .headers_mut()
+//!         .insert("X-Tenant", HeaderValue::from_static("acme"));
+//! });
+//! client.add_response_filter(|response: &reqwest::Response| {
+//!     println!("{} {}", response.status(), response.url());
+//! });
+//! ```
+
+use crate::error::Result;
+use async_trait::async_trait;
+
+/// A filter that can inspect and change every outgoing request
+///
+/// The request's headers, URL and body can be changed before it is sent.
+/// Returning an error aborts the request.
+#[async_trait]
+pub trait RequestFilter: Send + Sync {
+    /// Called with the fully built request just before it is sent
+    async fn filter(&self, request: &mut reqwest::Request) -> Result<()>;
+}
+
+/// A filter that can inspect every raw response before it is deserialized
+///
+/// Returning an error fails the request with that error.
+#[async_trait]
+pub trait ResponseFilter: Send + Sync {
+    /// Called with the raw response, including unsuccessful responses
+    async fn filter(&self, response: &reqwest::Response) -> Result<()>;
+}
+
+#[async_trait]
+impl<F> RequestFilter for F
+where
+    F: Fn(&mut reqwest::Request) + Send + Sync,
+{
+    async fn filter(&self, request: &mut reqwest::Request) -> Result<()> {
+        self(request);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<F> ResponseFilter for F
+where
+    F: Fn(&reqwest::Response) + Send + Sync,
+{
+    async fn filter(&self, response: &reqwest::Response) -> Result<()> {
+        self(response);
+        Ok(())
+    }
+}
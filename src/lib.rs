@@ -9,6 +9,8 @@
 //! - JSON serialization with serde
 //! - Type-safe request/response handling
 //! - Built on reqwest for reliable HTTP communication
+//! - `#[derive(ServiceStackRequest, ServiceStackResponse)]` with the `derive` feature
+//! - A synchronous `blocking::JsonServiceClient` with the `blocking` feature
 //!
 //! ## Example
 //!
@@ -40,8 +42,52 @@ use reqwest::Client;
 use serde::{Deserialize, Serialize};
 use std::time::Duration;
 
+pub mod api_response;
+pub mod api_result;
+mod batch;
+#[cfg(feature = "blocking")]
+pub mod blocking;
+pub mod body;
+pub mod cache;
+pub mod circuit_breaker;
+pub mod client;
+pub mod compression;
+pub mod download;
 pub mod error;
-pub use error::{Error, Result};
+pub mod filter;
+pub mod head;
+pub mod middleware;
+pub mod options;
+pub mod progress;
+mod query;
+mod response;
+pub mod response_status;
+pub mod retry;
+mod route;
+pub mod traits;
+pub mod upload;
+
+pub use api_response::ApiResponse;
+pub use api_result::ApiResult;
+pub use body::RequestBody;
+pub use cache::{CacheStats, CacheStore, CachedResponse, HttpCache, MemoryCache};
+pub use circuit_breaker::{CircuitBreaker, CircuitState};
+pub use client::{JsonServiceClient, JsonServiceClientBuilder, PredefinedRoutes};
+pub use compression::{Compression, RequestCompression};
+pub use download::Download;
+pub use error::{Error, Result, ServiceStackError};
+pub use filter::{RequestFilter, ResponseFilter};
+pub use head::HeadResponse;
+pub use middleware::{Middleware, Next};
+pub use options::RequestOptions;
+pub use progress::Progress;
+pub use response_status::{ResponseError, ResponseStatus};
+pub use retry::RetryPolicy;
+pub use traits::{HttpMethod, ServiceStackRequest, ServiceStackResponse};
+pub use upload::FilePart;
+
+#[cfg(feature = "derive")]
+pub use servicestack_derive::{ServiceStackRequest, ServiceStackResponse};
 
 /// ServiceStack HTTP client for making requests to ServiceStack services
 #[derive(Debug, Clone)]
@@ -82,13 +128,17 @@ impl ServiceStackClient {
     }
 
     /// Send a request and deserialize the JSON response
+    ///
+    /// `T` may be `()` for void responses, whose body is not deserialized. Unsuccessful
+    /// responses are returned as an `ApiError` with any `ResponseStatus`.
     async fn send_json<T>(&self, response: reqwest::Response) -> Result<T>
     where
-        T: for<'de> Deserialize<'de>,
+        T: for<'de> Deserialize<'de> + 'static,
     {
-        let response = response.error_for_status()?;
-        let result = response.json::<T>().await?;
-        Ok(result)
+        if !response.status().is_success() {
+            return Err(response::api_error(response).await);
+        }
+        response::read_raw(response).await
     }
 
     /// Create a new ServiceStack client with a custom reqwest Client
@@ -129,10 +179,24 @@ impl ServiceStackClient {
     /// A deserialized response of type `T`
     pub async fn get<T>(&self, path: &str) -> Result<T>
     where
-        T: for<'de> Deserialize<'de>,
+        T: for<'de> Deserialize<'de> + 'static,
+    {
+        self.get_with(path, RequestOptions::default()).await
+    }
+
+    /// Make a GET request with per-call options
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The API endpoint path
+    /// * `options` - Headers, query parameters, timeout and bearer token for this request
+    pub async fn get_with<T>(&self, path: &str, options: RequestOptions) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de> + 'static,
     {
-        let url = self.build_url(path);
-        let response = self.client.get(&url).send().await?;
+        let mut url = self.build_url(path);
+        options.append_query(&mut url);
+        let response = options.apply(self.client.get(&url))?.send().await?;
         self.send_json(response).await
     }
 
@@ -149,10 +213,30 @@ impl ServiceStackClient {
     pub async fn post<S, T>(&self, path: &str, body: &S) -> Result<T>
     where
         S: Serialize,
-        T: for<'de> Deserialize<'de>,
+        T: for<'de> Deserialize<'de> + 'static,
+    {
+        self.post_with(path, body, RequestOptions::default()).await
+    }
+
+    /// Make a POST request with a JSON body and per-call options
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The API endpoint path
+    /// * `body` - The request body to be serialized as JSON
+    /// * `options` - Headers, query parameters, timeout and bearer token for this request
+    pub async fn post_with<S, T>(&self, path: &str, body: &S, options: RequestOptions) -> Result<T>
+    where
+        S: Serialize,
+        T: for<'de> Deserialize<'de> + 'static,
     {
-        let url = self.build_url(path);
-        let response = self.client.post(&url).json(body).send().await?;
+        let mut url = self.build_url(path);
+        options.append_query(&mut url);
+        let response = options
+            .apply(self.client.post(&url))?
+            .json(body)
+            .send()
+            .await?;
         self.send_json(response).await
     }
 
@@ -169,10 +253,30 @@ impl ServiceStackClient {
     pub async fn put<S, T>(&self, path: &str, body: &S) -> Result<T>
     where
         S: Serialize,
-        T: for<'de> Deserialize<'de>,
+        T: for<'de> Deserialize<'de> + 'static,
+    {
+        self.put_with(path, body, RequestOptions::default()).await
+    }
+
+    /// Make a PUT request with a JSON body and per-call options
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The API endpoint path
+    /// * `body` - The request body to be serialized as JSON
+    /// * `options` - Headers, query parameters, timeout and bearer token for this request
+    pub async fn put_with<S, T>(&self, path: &str, body: &S, options: RequestOptions) -> Result<T>
+    where
+        S: Serialize,
+        T: for<'de> Deserialize<'de> + 'static,
     {
-        let url = self.build_url(path);
-        let response = self.client.put(&url).json(body).send().await?;
+        let mut url = self.build_url(path);
+        options.append_query(&mut url);
+        let response = options
+            .apply(self.client.put(&url))?
+            .json(body)
+            .send()
+            .await?;
         self.send_json(response).await
     }
 
@@ -187,10 +291,24 @@ impl ServiceStackClient {
     /// A deserialized response of type `T`
     pub async fn delete<T>(&self, path: &str) -> Result<T>
     where
-        T: for<'de> Deserialize<'de>,
+        T: for<'de> Deserialize<'de> + 'static,
     {
-        let url = self.build_url(path);
-        let response = self.client.delete(&url).send().await?;
+        self.delete_with(path, RequestOptions::default()).await
+    }
+
+    /// Make a DELETE request with per-call options
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The API endpoint path
+    /// * `options` - Headers, query parameters, timeout and bearer token for this request
+    pub async fn delete_with<T>(&self, path: &str, options: RequestOptions) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de> + 'static,
+    {
+        let mut url = self.build_url(path);
+        options.append_query(&mut url);
+        let response = options.apply(self.client.delete(&url))?.send().await?;
         self.send_json(response).await
     }
 
@@ -207,13 +325,58 @@ impl ServiceStackClient {
     pub async fn patch<S, T>(&self, path: &str, body: &S) -> Result<T>
     where
         S: Serialize,
-        T: for<'de> Deserialize<'de>,
+        T: for<'de> Deserialize<'de> + 'static,
+    {
+        self.patch_with(path, body, RequestOptions::default()).await
+    }
+
+    /// Make a PATCH request with a JSON body and per-call options
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The API endpoint path
+    /// * `body` - The request body to be serialized as JSON
+    /// * `options` - Headers, query parameters, timeout and bearer token for this request
+    pub async fn patch_with<S, T>(&self, path: &str, body: &S, options: RequestOptions) -> Result<T>
+    where
+        S: Serialize,
+        T: for<'de> Deserialize<'de> + 'static,
     {
-        let url = self.build_url(path);
-        let response = self.client.patch(&url).json(body).send().await?;
+        let mut url = self.build_url(path);
+        options.append_query(&mut url);
+        let response = options
+            .apply(self.client.patch(&url))?
+            .json(body)
+            .send()
+            .await?;
         self.send_json(response).await
     }
 
+    /// Make a HEAD request, returning only the response status and headers
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The API endpoint path
+    pub async fn head(&self, path: &str) -> Result<HeadResponse> {
+        self.head_with(path, RequestOptions::default()).await
+    }
+
+    /// Make a HEAD request with per-call options
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The API endpoint path
+    /// * `options` - Headers, query parameters, timeout and bearer token for this request
+    pub async fn head_with(&self, path: &str, options: RequestOptions) -> Result<HeadResponse> {
+        let mut url = self.build_url(path);
+        options.append_query(&mut url);
+        let response = options.apply(self.client.head(&url))?.send().await?;
+        if !response.status().is_success() {
+            return Err(response::api_error(response).await);
+        }
+        Ok(HeadResponse::from_response(&response))
+    }
+
     /// Get the base URL of this client
     pub fn base_url(&self) -> &str {
         &self.base_url
@@ -236,7 +399,7 @@ mod tests {
             .timeout(Duration::from_secs(60))
             .build()
             .unwrap();
-        
+
         let client = ServiceStackClient::with_client("https://api.example.com", custom_client);
         assert_eq!(client.base_url(), "https://api.example.com");
     }
@@ -0,0 +1,168 @@
+//! Composable middleware around the client's HTTP transport
+//!
+//! Middleware wraps every request sent by a [`JsonServiceClient`](crate::JsonServiceClient),
+//! similar to tower layers. Each middleware receives the request and a [`Next`] handle
+//! and decides whether to call `next.run(request)`, which lets it rewrite the request,
+//! short-circuit with its own response, inspect the result or retry.
+//!
+//! Middleware runs in a defined order, outermost first:
+//!
+//! 1. middleware added with `add_middleware`, in registration order
+//! 2. [`RequestCompression`](crate::RequestCompression), when request compression is set
+//! 3. [`Retry`](crate::retry::Retry), when a retry policy is set and the request is retryable
+//! 4. [`CircuitBreaker`](crate::CircuitBreaker), when a circuit breaker is set
+//! 5. [`BearerAuth`], when a bearer token is set
+//! 6. [`ErrorMapping`], which turns unsuccessful responses into
+//!    [`ServiceStackError::ApiError`]
+//! 7. [`HttpCache`](crate::HttpCache), when a cache is set
+//! 8. the registered request and response [filters](crate::filter)
+//! 9. the upload progress callback of the call's
+//!    [`RequestOptions`](crate::RequestOptions), if any
+//!
+//! # Example
+//!
+//! ```
+//! use async_trait::async_trait;
+//! use servicestack::middleware::{Middleware, Next};
+//! use servicestack::{JsonServiceClient, Result};
+//!
+//! struct Logging;
+//!
+//! #[async_trait]
+//! impl Middleware for Logging {
+//!     async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response> {
+//!         println!("{} {}", request.method(), request.url());
+//!         let result = next.run(request).await;
+//!         println!("succeeded: {}", result.is_ok());
+//!         result
+//!     }
+//! }
+//!
+//! let mut client = JsonServiceClient::new("https://api.example.com");
+//! client.add_middleware(Logging);
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use crate::filter::{RequestFilter, ResponseFilter};
+use crate::response;
+use async_trait::async_trait;
+use reqwest::header::{HeaderValue, AUTHORIZATION};
+use std::sync::Arc;
+
+/// A middleware that wraps the sending of a request
+#[async_trait]
+pub trait Middleware: Send + Sync {
+    /// Handles a request, usually by calling `next.run(request)`
+    async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response>;
+}
+
+/// The remainder of the middleware stack, ending with the HTTP transport
+///
+/// `Next` is `Copy`, so middleware can call [`run`](Self::run) more than once,
+/// e.g. to retry with a [cloned](reqwest::Request::try_clone) request.
+#[derive(Clone, Copy)]
+pub struct Next<'a> {
+    http_client: &'a reqwest::Client,
+    middleware: &'a [Arc<dyn Middleware>],
+}
+
+impl<'a> Next<'a> {
+    pub(crate) fn new(
+        http_client: &'a reqwest::Client,
+        middleware: &'a [Arc<dyn Middleware>],
+    ) -> Self {
+        Self {
+            http_client,
+            middleware,
+        }
+    }
+
+    /// Passes the request to the next middleware, or sends it if none remain
+    pub async fn run(self, request: reqwest::Request) -> Result<reqwest::Response> {
+        match self.middleware.split_first() {
+            Some((current, rest)) => {
+                current
+                    .handle(request, Next::new(self.http_client, rest))
+                    .await
+            }
+            None => Ok(self.http_client.execute(request).await?),
+        }
+    }
+
+    /// Returns the HTTP client that sends the request at the end of the stack
+    pub fn http_client(&self) -> &'a reqwest::Client {
+        self.http_client
+    }
+}
+
+/// Adds an `Authorization: Bearer` header to requests that don't already have one
+#[derive(Debug, Clone)]
+pub struct BearerAuth {
+    token: String,
+}
+
+impl BearerAuth {
+    /// Creates a middleware that authenticates with the given bearer token
+    pub fn new(token: impl Into<String>) -> Self {
+        Self {
+            token: token.into(),
+        }
+    }
+}
+
+#[async_trait]
+impl Middleware for BearerAuth {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response> {
+        if !request.headers().contains_key(AUTHORIZATION) {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", self.token))
+                .map_err(|e| ServiceStackError::InvalidHeader(format!("Authorization: {}", e)))?;
+            value.set_sensitive(true);
+            request.headers_mut().insert(AUTHORIZATION, value);
+        }
+        next.run(request).await
+    }
+}
+
+/// Turns unsuccessful responses into [`ServiceStackError::ApiError`], parsing any
+/// ServiceStack `ResponseStatus` in the body
+#[derive(Debug, Clone, Copy, Default)]
+pub struct ErrorMapping;
+
+#[async_trait]
+impl Middleware for ErrorMapping {
+    async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response> {
+        let response = next.run(request).await?;
+        if !response.status().is_success() {
+            return Err(response::api_error(response).await);
+        }
+        Ok(response)
+    }
+}
+
+/// Runs the client's request and response filters around the transport
+pub(crate) struct Filters {
+    pub(crate) request_filters: Vec<Arc<dyn RequestFilter>>,
+    pub(crate) response_filters: Vec<Arc<dyn ResponseFilter>>,
+}
+
+#[async_trait]
+impl Middleware for Filters {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response> {
+        for filter in &self.request_filters {
+            filter.filter(&mut request).await?;
+        }
+        let response = next.run(request).await?;
+        for filter in &self.response_filters {
+            filter.filter(&response).await?;
+        }
+        Ok(response)
+    }
+}
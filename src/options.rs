@@ -0,0 +1,246 @@
+//! Per-call request options
+//!
+//! [`RequestOptions`] changes a single call without reconfiguring the client: extra
+//! headers, a timeout override, extra query parameters, a bearer token override, an
+//! upload progress callback and a cache bypass.
+//! They are accepted by [`JsonServiceClient::send_with`](crate::JsonServiceClient::send_with)
+//! and the `*_with` methods of [`ServiceStackClient`](crate::ServiceStackClient).
+//!
+//! # Example
+//!
+//! ```no_run
+//! use servicestack::{JsonServiceClient, RequestOptions, ServiceStackRequest, ServiceStackResponse};
+//! use serde::{Deserialize, Serialize};
+//! use std::time::Duration;
+//!
+//! #[derive(Serialize)]
+//! struct Hello {
+//!     name: String,
+//! }
+//!
+//! #[derive(Deserialize)]
+//! struct HelloResponse {
+//!     result: String,
+//! }
+//!
+//! impl ServiceStackResponse for HelloResponse {}
+//!
+//! impl ServiceStackRequest for Hello {
+//!     type Response = HelloResponse;
+//! }
+//!
+//! # async fn run() -> servicestack::Result<()> {
+//! let client = JsonServiceClient::new("https://api.example.com");
+//! let options = RequestOptions::new()
+//!     .header("X-Request-Id", "42")
+//!     .query("debug", "true")
+//!     .timeout(Duration::from_secs(5));
+//! let request = Hello { name: "World".to_string() };
+//! let response = client.send_with(request, options).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use crate::progress::{Progress, ProgressCallback};
+use crate::query;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CACHE_CONTROL};
+use std::fmt;
+use std::time::Duration;
+
+/// Options that apply to a single request
+///
+/// Invalid header names or values are reported when the request is sent.
+#[derive(Default)]
+pub struct RequestOptions {
+    headers: HeaderMap,
+    timeout: Option<Duration>,
+    query: Vec<(String, String)>,
+    bearer_token: Option<String>,
+    upload_progress: Option<ProgressCallback>,
+    error: Option<ServiceStackError>,
+}
+
+impl fmt::Debug for RequestOptions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RequestOptions")
+            .field("headers", &self.headers)
+            .field("timeout", &self.timeout)
+            .field("query", &self.query)
+            .field(
+                "bearer_token",
+                &self.bearer_token.as_ref().map(|_| "<redacted>"),
+            )
+            .field("upload_progress", &self.upload_progress.is_some())
+            .field("error", &self.error)
+            .finish()
+    }
+}
+
+impl RequestOptions {
+    /// Creates empty options
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a header to the request
+    pub fn header(mut self, name: impl AsRef<str>, value: impl AsRef<str>) -> Self {
+        let name = name.as_ref();
+        match (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value.as_ref()),
+        ) {
+            (Ok(name), Ok(value)) => {
+                self.headers.append(name, value);
+            }
+            (Err(e), _) => {
+                self.set_error(ServiceStackError::InvalidHeader(format!("{}: {}", name, e)))
+            }
+            (_, Err(e)) => {
+                self.set_error(ServiceStackError::InvalidHeader(format!("{}: {}", name, e)))
+            }
+        }
+        self
+    }
+
+    /// Adds headers to the request
+    pub fn headers(mut self, headers: HeaderMap) -> Self {
+        self.headers.extend(headers);
+        self
+    }
+
+    /// Overrides the client's total timeout for the request
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Adds a query string parameter, sent with the name as given
+    pub fn query(mut self, name: impl Into<String>, value: impl ToString) -> Self {
+        self.query.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Authenticates the request with this bearer token instead of the client's
+    pub fn bearer_token(mut self, token: impl Into<String>) -> Self {
+        self.bearer_token = Some(token.into());
+        self
+    }
+
+    /// Skips fresh responses of the client's [`HttpCache`] for this request
+    ///
+    /// Sends `Cache-Control: no-cache`, so a cached response is revalidated with the
+    /// service rather than served without a request.
+    ///
+    /// [`HttpCache`]: crate::HttpCache
+    pub fn bypass_cache(mut self) -> Self {
+        self.headers
+            .append(CACHE_CONTROL, HeaderValue::from_static("no-cache"));
+        self
+    }
+
+    /// Calls `callback` as the request body is sent
+    ///
+    /// Reports progress for the JSON bodies, multipart uploads and [`RequestBody`]
+    /// streams sent by [`JsonServiceClient`]; a retried request reports its progress
+    /// again from zero.
+    ///
+    /// [`RequestBody`]: crate::RequestBody
+    /// [`JsonServiceClient`]: crate::JsonServiceClient
+    pub fn on_upload_progress(
+        mut self,
+        callback: impl FnMut(Progress) + Send + Sync + 'static,
+    ) -> Self {
+        self.upload_progress = Some(Box::new(callback));
+        self
+    }
+
+    /// Takes the upload progress callback, which is run as middleware
+    pub(crate) fn take_upload_progress(&mut self) -> Option<ProgressCallback> {
+        self.upload_progress.take()
+    }
+
+    /// Appends the extra query string parameters to a URL
+    pub(crate) fn append_query(&self, url: &mut String) {
+        query::append_query(url, &query::encode_pairs(&self.query));
+    }
+
+    /// Applies the headers, timeout and bearer token to a request
+    ///
+    /// Returns the first invalid header, if any.
+    pub(crate) fn apply(
+        self,
+        request_builder: reqwest::RequestBuilder,
+    ) -> Result<reqwest::RequestBuilder> {
+        if let Some(error) = self.error {
+            return Err(error);
+        }
+
+        let mut headers = self.headers;
+        if let Some(token) = &self.bearer_token {
+            let mut value = HeaderValue::from_str(&format!("Bearer {}", token))
+                .map_err(|e| ServiceStackError::InvalidHeader(format!("Authorization: {}", e)))?;
+            value.set_sensitive(true);
+            headers.insert(AUTHORIZATION, value);
+        }
+
+        let request_builder = request_builder.headers(headers);
+        Ok(match self.timeout {
+            Some(timeout) => request_builder.timeout(timeout),
+            None => request_builder,
+        })
+    }
+
+    /// Records the first invalid option, reported when the request is sent
+    fn set_error(&mut self, error: ServiceStackError) {
+        self.error.get_or_insert(error);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_apply() {
+        let options = RequestOptions::new()
+            .header("X-Tenant", "acme")
+            .bearer_token("token")
+            .timeout(Duration::from_secs(5));
+        let client = reqwest::Client::new();
+        let request = options
+            .apply(client.get("https://api.example.com/hello"))
+            .unwrap()
+            .build()
+            .unwrap();
+
+        assert_eq!(request.headers()["X-Tenant"], "acme");
+        assert_eq!(request.headers()[AUTHORIZATION], "Bearer token");
+        assert_eq!(request.timeout(), Some(&Duration::from_secs(5)));
+    }
+
+    #[test]
+    fn test_append_query() {
+        let options = RequestOptions::new()
+            .query("debug", true)
+            .query("tag", "a b");
+        let mut url = "/hello?name=World".to_string();
+        options.append_query(&mut url);
+        assert_eq!(url, "/hello?name=World&debug=true&tag=a%20b");
+    }
+
+    #[test]
+    fn test_invalid_header() {
+        let options = RequestOptions::new().header("Bad Header", "value");
+        let client = reqwest::Client::new();
+        let result = options.apply(client.get("https://api.example.com/hello"));
+        assert!(matches!(result, Err(ServiceStackError::InvalidHeader(_))));
+    }
+
+    #[test]
+    fn test_debug_redacts_bearer_token() {
+        let debug = format!("{:?}", RequestOptions::new().bearer_token("secret-token"));
+        assert!(!debug.contains("secret-token"));
+        assert!(debug.contains("<redacted>"));
+    }
+}
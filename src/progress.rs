@@ -0,0 +1,112 @@
+//! Progress reporting for streamed transfers
+
+use crate::error::Result;
+use crate::middleware::{Middleware, Next};
+use async_trait::async_trait;
+use bytes::Bytes;
+use futures_util::{stream, Stream, TryStreamExt};
+use http_body_util::BodyExt;
+use reqwest::header::{HeaderValue, CONTENT_LENGTH};
+use std::error::Error as StdError;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+
+/// The size of the chunks in-memory bodies are sent in, so their progress is reported
+const CHUNK_SIZE: usize = 16 * 1024;
+
+/// The progress of a download or upload
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Progress {
+    /// Bytes transferred so far
+    pub transferred: u64,
+    /// Total bytes to transfer, when known
+    pub total: Option<u64>,
+}
+
+impl Progress {
+    /// Returns the fraction transferred, between `0.0` and `1.0`, when the total is known
+    pub fn fraction(&self) -> Option<f64> {
+        match self.total {
+            Some(0) => Some(1.0),
+            Some(total) => Some((self.transferred as f64 / total as f64).min(1.0)),
+            None => None,
+        }
+    }
+}
+
+/// A callback invoked as a transfer progresses
+pub(crate) type ProgressCallback = Box<dyn FnMut(Progress) + Send + Sync>;
+
+type BodyStream = Pin<
+    Box<
+        dyn Stream<Item = std::result::Result<Bytes, Box<dyn StdError + Send + Sync>>>
+            + Send
+            + Sync,
+    >,
+>;
+
+/// Reports the bytes sent of a request body
+///
+/// Runs innermost, so each retry attempt reports its progress from zero.
+#[derive(Clone)]
+pub(crate) struct UploadProgress {
+    callback: Arc<Mutex<ProgressCallback>>,
+}
+
+impl UploadProgress {
+    pub(crate) fn new(callback: ProgressCallback) -> Self {
+        Self {
+            callback: Arc::new(Mutex::new(callback)),
+        }
+    }
+
+    /// Wraps a body in a stream that reports the bytes read from it
+    fn wrap(&self, body: reqwest::Body, total: Option<u64>) -> reqwest::Body {
+        let chunks: BodyStream = match body.as_bytes() {
+            Some(bytes) => {
+                let bytes = Bytes::copy_from_slice(bytes);
+                let chunks = (0..bytes.len())
+                    .step_by(CHUNK_SIZE)
+                    .map(move |start| Ok(bytes.slice(start..bytes.len().min(start + CHUNK_SIZE))))
+                    .collect::<Vec<_>>();
+                Box::pin(stream::iter(chunks))
+            }
+            None => Box::pin(body.into_data_stream().map_err(Into::into)),
+        };
+
+        let callback = self.callback.clone();
+        let mut transferred = 0;
+        reqwest::Body::wrap_stream(chunks.inspect_ok(move |chunk| {
+            transferred += chunk.len() as u64;
+            let mut callback = callback.lock().unwrap_or_else(|e| e.into_inner());
+            callback(Progress { transferred, total });
+        }))
+    }
+}
+
+#[async_trait]
+impl Middleware for UploadProgress {
+    async fn handle(
+        &self,
+        mut request: reqwest::Request,
+        next: Next<'_>,
+    ) -> Result<reqwest::Response> {
+        if let Some(body) = request.body_mut().take() {
+            let total = request
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|value| value.to_str().ok())
+                .and_then(|value| value.parse().ok())
+                .or_else(|| body.as_bytes().map(|bytes| bytes.len() as u64));
+
+            // The wrapped body has no size hint, so keep a known length
+            if let Some(total) = total {
+                request
+                    .headers_mut()
+                    .insert(CONTENT_LENGTH, HeaderValue::from(total));
+            }
+            *request.body_mut() = Some(self.wrap(body, total));
+        }
+        next.run(request).await
+    }
+}
@@ -0,0 +1,195 @@
+//! Query string serialization for request DTOs
+//!
+//! Follows ServiceStack's conventions for sending DTOs on the query string:
+//! keys are camelCased, arrays are sent as comma-separated values and
+//! `None`/`null` fields are omitted. Fields are sent in declaration order,
+//! which relies on `serde_json`'s `preserve_order` feature.
+
+use crate::error::{Result, ServiceStackError};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Characters that are percent-encoded in query string keys and values
+///
+/// Everything except the RFC 3986 unreserved characters is encoded.
+const QUERY_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// Serializes a request DTO into a percent-encoded query string (without the leading `?`)
+///
+/// Returns an empty string when the DTO has no fields to send.
+pub(crate) fn to_query_string<T: Serialize + ?Sized>(request: &T) -> Result<String> {
+    match serde_json::to_value(request)? {
+        Value::Object(fields) => Ok(encode_fields(&fields)),
+        Value::Null => Ok(String::new()),
+        _ => Err(ServiceStackError::Other(
+            "Request DTO must serialize to a JSON object to be sent as a query string".to_string(),
+        )),
+    }
+}
+
+/// Encodes the fields of a serialized DTO as `key=value` pairs joined by `&`
+pub(crate) fn encode_fields(fields: &Map<String, Value>) -> String {
+    encode_pairs(&field_pairs(fields))
+}
+
+/// Converts the fields of a serialized DTO into `(key, value)` pairs
+///
+/// Keys are camelCased and `null` fields are omitted, as for query strings.
+pub(crate) fn field_pairs(fields: &Map<String, Value>) -> Vec<(String, String)> {
+    fields
+        .iter()
+        .filter_map(|(key, value)| Some((to_camel_case(key), query_value(value)?)))
+        .collect()
+}
+
+/// Encodes `name=value` pairs as they are, without renaming the keys
+pub(crate) fn encode_pairs(pairs: &[(String, String)]) -> String {
+    pairs
+        .iter()
+        .map(|(name, value)| {
+            format!(
+                "{}={}",
+                utf8_percent_encode(name, QUERY_ENCODE_SET),
+                utf8_percent_encode(value, QUERY_ENCODE_SET)
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("&")
+}
+
+/// Appends a query string to a URL, using `&` if the URL already has one
+pub(crate) fn append_query(url: &mut String, query: &str) {
+    if query.is_empty() {
+        return;
+    }
+    url.push(if url.contains('?') { '&' } else { '?' });
+    url.push_str(query);
+}
+
+/// Converts a single field value into its query string representation
+///
+/// Returns `None` for values that should be omitted from the query string.
+pub(crate) fn query_value(value: &Value) -> Option<String> {
+    match value {
+        Value::Null => None,
+        Value::Array(items) => Some(
+            items
+                .iter()
+                .filter_map(query_value)
+                .map(|item| quote_list_item(&item))
+                .collect::<Vec<_>>()
+                .join(","),
+        ),
+        _ => Some(scalar_value(value)),
+    }
+}
+
+/// Converts a scalar (or nested object) value into a string
+pub(crate) fn scalar_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => s.clone(),
+        Value::Bool(b) => b.to_string(),
+        Value::Number(n) => n.to_string(),
+        _ => value.to_string(),
+    }
+}
+
+/// Quotes a list item that contains a comma or quote so it survives comma-splitting
+fn quote_list_item(item: &str) -> String {
+    if item.contains(',') || item.contains('"') {
+        format!("\"{}\"", item.replace('"', "\"\""))
+    } else {
+        item.to_string()
+    }
+}
+
+/// Converts a `snake_case` field name to `camelCase`
+///
+/// Names without underscores (including names that are already camelCase) are
+/// returned unchanged apart from lowercasing the first character.
+pub(crate) fn to_camel_case(name: &str) -> String {
+    let mut result = String::with_capacity(name.len());
+    let mut upper_next = false;
+    for (i, c) in name.chars().enumerate() {
+        if c == '_' {
+            upper_next = i > 0;
+        } else if upper_next {
+            result.extend(c.to_uppercase());
+            upper_next = false;
+        } else if result.is_empty() {
+            result.extend(c.to_lowercase());
+        } else {
+            result.push(c);
+        }
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Serialize;
+
+    #[derive(Serialize)]
+    struct GetUserRequest {
+        id: u32,
+        include_deleted: bool,
+        tags: Vec<String>,
+        name: Option<String>,
+    }
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("include_deleted"), "includeDeleted");
+        assert_eq!(to_camel_case("id"), "id");
+        assert_eq!(to_camel_case("includeDeleted"), "includeDeleted");
+        assert_eq!(to_camel_case("Name"), "name");
+    }
+
+    #[test]
+    fn test_query_string_conventions() {
+        let request = GetUserRequest {
+            id: 1,
+            include_deleted: true,
+            tags: vec!["a".to_string(), "b c".to_string()],
+            name: None,
+        };
+        assert_eq!(
+            to_query_string(&request).unwrap(),
+            "id=1&includeDeleted=true&tags=a%2Cb%20c"
+        );
+    }
+
+    #[test]
+    fn test_query_string_quotes_list_items_with_commas() {
+        let fields = serde_json::json!({ "names": ["x,y", "z"] });
+        assert_eq!(
+            encode_fields(fields.as_object().unwrap()),
+            "names=%22x%2Cy%22%2Cz"
+        );
+    }
+
+    #[test]
+    fn test_append_query() {
+        let mut url = "/users".to_string();
+        append_query(&mut url, "");
+        assert_eq!(url, "/users");
+        append_query(&mut url, "id=1");
+        append_query(&mut url, "skip=2");
+        assert_eq!(url, "/users?id=1&skip=2");
+    }
+
+    #[test]
+    fn test_encode_pairs() {
+        let pairs = vec![
+            ("include_deleted".to_string(), "true".to_string()),
+            ("q".to_string(), "a b&c".to_string()),
+        ];
+        assert_eq!(encode_pairs(&pairs), "include_deleted=true&q=a%20b%26c");
+    }
+}
@@ -0,0 +1,96 @@
+//! Response body handling shared by the clients
+
+use crate::error::{Result, ServiceStackError};
+use crate::traits::ServiceStackResponse;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::any::TypeId;
+
+/// Reads a response body and deserializes it from JSON
+///
+/// An empty body (e.g. `204 No Content`) is deserialized from JSON `null`, so
+/// void responses such as `()` or `Option<T>` succeed without a body.
+pub(crate) async fn read_json<T: DeserializeOwned>(response: reqwest::Response) -> Result<T> {
+    let body = response.bytes().await?;
+    from_body(&body)
+}
+
+/// Reads the response DTO from a successful response
+///
+/// Void responses (ServiceStack's `IReturnVoid`) are not deserialized, whatever their body.
+pub(crate) async fn read_body<T: ServiceStackResponse>(response: reqwest::Response) -> Result<T> {
+    if T::IS_VOID {
+        void()
+    } else {
+        read_json(response).await
+    }
+}
+
+/// Reads the response of a raw request, which may be any deserializable type
+///
+/// A `()` response is void and its body is not deserialized.
+pub(crate) async fn read_raw<T: DeserializeOwned + 'static>(
+    response: reqwest::Response,
+) -> Result<T> {
+    if is_void::<T>() {
+        void()
+    } else {
+        read_json(response).await
+    }
+}
+
+/// Returns whether a raw response type is `()`, the void response
+pub(crate) fn is_void<T: 'static>() -> bool {
+    TypeId::of::<T>() == TypeId::of::<()>()
+}
+
+/// Reads an unsuccessful response into an `ApiError`, parsing any `ResponseStatus`
+pub(crate) async fn api_error(response: reqwest::Response) -> ServiceStackError {
+    let status = response.status().as_u16();
+    let body = response.text().await.unwrap_or_default();
+    ServiceStackError::from_response_body(status, body)
+}
+
+/// Deserializes a JSON body, treating an empty or whitespace-only body as `null`
+pub(crate) fn from_body<T: DeserializeOwned>(body: &[u8]) -> Result<T> {
+    if body.iter().all(u8::is_ascii_whitespace) {
+        return Ok(serde_json::from_value(Value::Null)?);
+    }
+    Ok(serde_json::from_slice(body)?)
+}
+
+/// Returns the value of a void response without reading the body
+pub(crate) fn void<T: DeserializeOwned>() -> Result<T> {
+    Ok(serde_json::from_value(Value::Null)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Deserialize, Debug, PartialEq)]
+    struct Dto {
+        id: u32,
+    }
+
+    #[test]
+    fn test_empty_body_is_null() {
+        from_body::<()>(b"").unwrap();
+        from_body::<()>(b"  \n").unwrap();
+        assert_eq!(from_body::<Option<Dto>>(b"").unwrap(), None);
+        assert!(from_body::<Dto>(b"").is_err());
+    }
+
+    #[test]
+    fn test_is_void() {
+        assert!(is_void::<()>());
+        assert!(!is_void::<Option<Dto>>());
+        assert!(!is_void::<serde_json::Value>());
+    }
+
+    #[test]
+    fn test_body_is_deserialized() {
+        assert_eq!(from_body::<Dto>(br#"{"id":1}"#).unwrap(), Dto { id: 1 });
+    }
+}
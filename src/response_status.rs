@@ -0,0 +1,206 @@
+//! ServiceStack `ResponseStatus` error details
+
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// Structured error details returned by ServiceStack services
+///
+/// ServiceStack returns these in the `responseStatus` property of error
+/// responses, e.g. `{"responseStatus":{"errorCode":"NotFound","message":"User not found"}}`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResponseStatus {
+    /// The error code, usually the exception type or validation error code
+    #[serde(alias = "ErrorCode")]
+    pub error_code: Option<String>,
+
+    /// The error message
+    #[serde(alias = "Message")]
+    pub message: Option<String>,
+
+    /// The server stack trace, only returned when debug mode is enabled
+    #[serde(alias = "StackTrace")]
+    pub stack_trace: Option<String>,
+
+    /// Field-level errors, e.g. from validators
+    #[serde(alias = "Errors", deserialize_with = "null_as_default")]
+    pub errors: Vec<ResponseError>,
+
+    /// Additional metadata attached to the error
+    #[serde(alias = "Meta")]
+    pub meta: Option<HashMap<String, String>>,
+}
+
+/// A field-level error within a [`ResponseStatus`]
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ResponseError {
+    /// The error code, e.g. `NotEmpty`
+    #[serde(alias = "ErrorCode")]
+    pub error_code: Option<String>,
+
+    /// The name of the field the error applies to
+    #[serde(alias = "FieldName")]
+    pub field_name: Option<String>,
+
+    /// The error message
+    #[serde(alias = "Message")]
+    pub message: Option<String>,
+
+    /// Additional metadata attached to the error
+    #[serde(alias = "Meta")]
+    pub meta: Option<HashMap<String, String>>,
+}
+
+impl ResponseStatus {
+    /// Parses the `ResponseStatus` from a ServiceStack error response body
+    ///
+    /// Returns `None` if the body is not JSON or has no `responseStatus` property.
+    pub fn from_error_body(body: &str) -> Option<Self> {
+        let value: Value = serde_json::from_str(body).ok()?;
+        let status = value
+            .get("responseStatus")
+            .or_else(|| value.get("ResponseStatus"))?;
+        if status.is_null() {
+            return None;
+        }
+        serde_json::from_value(status.clone()).ok()
+    }
+
+    /// Returns whether this status contains field-level validation errors
+    pub fn is_validation_error(&self) -> bool {
+        !self.errors.is_empty()
+    }
+
+    /// Returns the errors for a field, matching the field name case-insensitively
+    pub fn field_errors(&self, field_name: &str) -> Vec<&ResponseError> {
+        self.errors
+            .iter()
+            .filter(|error| error.is_for_field(field_name))
+            .collect()
+    }
+
+    /// Returns the first error for a field, matching the field name case-insensitively
+    pub fn field_error(&self, field_name: &str) -> Option<&ResponseError> {
+        self.errors
+            .iter()
+            .find(|error| error.is_for_field(field_name))
+    }
+
+    /// Returns the message summarizing this error
+    ///
+    /// This is the top-level message, falling back to the first field error's
+    /// message and then the error code.
+    pub fn summary_message(&self) -> Option<&str> {
+        self.message
+            .as_deref()
+            .filter(|message| !message.is_empty())
+            .or_else(|| self.errors.iter().find_map(|e| e.message.as_deref()))
+            .or(self.error_code.as_deref())
+    }
+}
+
+impl ResponseError {
+    /// Returns whether this error applies to a field, matching the name case-insensitively
+    pub fn is_for_field(&self, field_name: &str) -> bool {
+        self.field_name
+            .as_deref()
+            .is_some_and(|name| name.eq_ignore_ascii_case(field_name))
+    }
+}
+
+/// Deserializes `null` as the type's default value
+fn null_as_default<'de, D, T>(deserializer: D) -> std::result::Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: Default + Deserialize<'de>,
+{
+    Ok(Option::<T>::deserialize(deserializer)?.unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_error_body() {
+        let body = r#"{
+            "responseStatus": {
+                "errorCode": "ValidationException",
+                "message": "Validation failed",
+                "errors": [
+                    {"errorCode": "NotEmpty", "fieldName": "Name", "message": "'Name' must not be empty."}
+                ],
+                "meta": {"requestId": "abc"}
+            }
+        }"#;
+        let status = ResponseStatus::from_error_body(body).unwrap();
+        assert_eq!(status.error_code.as_deref(), Some("ValidationException"));
+        assert_eq!(status.message.as_deref(), Some("Validation failed"));
+        assert_eq!(status.errors.len(), 1);
+        assert_eq!(status.errors[0].field_name.as_deref(), Some("Name"));
+        assert_eq!(status.meta.unwrap()["requestId"], "abc");
+    }
+
+    #[test]
+    fn test_parse_pascal_case_error_body() {
+        let body =
+            r#"{"ResponseStatus":{"ErrorCode":"NotFound","Message":"Missing","Errors":null}}"#;
+        let status = ResponseStatus::from_error_body(body).unwrap();
+        assert_eq!(status.error_code.as_deref(), Some("NotFound"));
+        assert_eq!(status.message.as_deref(), Some("Missing"));
+        assert!(status.errors.is_empty());
+    }
+
+    #[test]
+    fn test_field_errors() {
+        let status = ResponseStatus {
+            errors: vec![
+                ResponseError {
+                    field_name: Some("Name".to_string()),
+                    message: Some("Required".to_string()),
+                    ..Default::default()
+                },
+                ResponseError {
+                    field_name: Some("NAME".to_string()),
+                    message: Some("Too short".to_string()),
+                    ..Default::default()
+                },
+                ResponseError {
+                    field_name: Some("Email".to_string()),
+                    ..Default::default()
+                },
+            ],
+            ..Default::default()
+        };
+        assert!(status.is_validation_error());
+        assert_eq!(status.field_errors("name").len(), 2);
+        assert_eq!(
+            status.field_error("name").unwrap().message.as_deref(),
+            Some("Required")
+        );
+        assert!(status.field_error("Age").is_none());
+        assert_eq!(status.summary_message(), Some("Required"));
+    }
+
+    #[test]
+    fn test_summary_message() {
+        let mut status = ResponseStatus {
+            error_code: Some("NotFound".to_string()),
+            ..Default::default()
+        };
+        assert!(!status.is_validation_error());
+        assert_eq!(status.summary_message(), Some("NotFound"));
+
+        status.message = Some("User not found".to_string());
+        assert_eq!(status.summary_message(), Some("User not found"));
+    }
+
+    #[test]
+    fn test_non_servicestack_error_body() {
+        assert!(ResponseStatus::from_error_body("Not Found").is_none());
+        assert!(ResponseStatus::from_error_body(r#"{"error":"x"}"#).is_none());
+        assert!(ResponseStatus::from_error_body(r#"{"responseStatus":null}"#).is_none());
+    }
+}
@@ -0,0 +1,233 @@
+//! Automatic retries with exponential backoff
+//!
+//! A [`RetryPolicy`] set on a [`JsonServiceClient`](crate::JsonServiceClient) retries
+//! transient failures such as `502`/`503`/`504` responses, timeouts and connection
+//! errors. By default only idempotent methods (GET, HEAD, OPTIONS, PUT, DELETE) are
+//! retried; a request type can opt in or out with
+//! [`ServiceStackRequest::retryable`](crate::ServiceStackRequest::retryable).
+//!
+//! # Example
+//!
+//! ```
+//! use servicestack::{JsonServiceClient, RetryPolicy};
+//! use std::time::Duration;
+//!
+//! let client = JsonServiceClient::builder("https://api.example.com")
+//!     .retry_policy(
+//!         RetryPolicy::new()
+//!             .max_attempts(5)
+//!             .initial_backoff(Duration::from_millis(200)),
+//!     )
+//!     .build()
+//!     .unwrap();
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use crate::middleware::{Middleware, Next};
+use async_trait::async_trait;
+use std::time::Duration;
+
+/// Configures which failures are retried and how long to wait between attempts
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    multiplier: f64,
+    jitter: bool,
+    retry_statuses: Vec<u16>,
+    retry_connect_errors: bool,
+    retry_timeouts: bool,
+    retry_request_errors: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            multiplier: 2.0,
+            jitter: true,
+            retry_statuses: vec![502, 503, 504],
+            retry_connect_errors: true,
+            retry_timeouts: true,
+            retry_request_errors: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Creates a policy with 3 attempts and exponential backoff from 100ms, retrying
+    /// 502/503/504 responses, timeouts and connection errors
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the maximum number of attempts, including the first one
+    pub fn max_attempts(mut self, max_attempts: u32) -> Self {
+        self.max_attempts = max_attempts.max(1);
+        self
+    }
+
+    /// Sets the delay before the first retry
+    pub fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+        self
+    }
+
+    /// Sets the upper bound for the delay between attempts
+    pub fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+        self
+    }
+
+    /// Sets the factor the delay grows by after each attempt
+    pub fn multiplier(mut self, multiplier: f64) -> Self {
+        self.multiplier = multiplier.max(1.0);
+        self
+    }
+
+    /// Controls whether delays are randomized ("full jitter") to spread out retries
+    pub fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    /// Sets the HTTP status codes that are retried
+    pub fn retry_statuses(mut self, statuses: impl IntoIterator<Item = u16>) -> Self {
+        self.retry_statuses = statuses.into_iter().collect();
+        self
+    }
+
+    /// Controls whether failures to connect are retried
+    pub fn retry_connect_errors(mut self, retry: bool) -> Self {
+        self.retry_connect_errors = retry;
+        self
+    }
+
+    /// Controls whether timeouts are retried
+    pub fn retry_timeouts(mut self, retry: bool) -> Self {
+        self.retry_timeouts = retry;
+        self
+    }
+
+    /// Controls whether errors while sending a request, such as connection resets, are retried
+    pub fn retry_request_errors(mut self, retry: bool) -> Self {
+        self.retry_request_errors = retry;
+        self
+    }
+
+    /// Returns the maximum number of attempts, including the first one
+    pub fn attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Returns whether the error is one this policy retries
+    pub fn should_retry(&self, error: &ServiceStackError) -> bool {
+        match error {
+            ServiceStackError::ApiError { status, .. } => self.retry_statuses.contains(status),
+            ServiceStackError::RequestError(e) => {
+                (self.retry_timeouts && e.is_timeout())
+                    || (self.retry_connect_errors && e.is_connect())
+                    || (self.retry_request_errors && e.is_request())
+            }
+            _ => false,
+        }
+    }
+
+    /// Returns the delay before the given retry, where `1` is the first retry
+    pub fn backoff(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1).min(i32::MAX as u32) as i32;
+        let delay = self.initial_backoff.as_secs_f64() * self.multiplier.powi(exponent);
+        let delay = delay.min(self.max_backoff.as_secs_f64());
+        let delay = if self.jitter {
+            delay * fastrand::f64()
+        } else {
+            delay
+        };
+        Duration::from_secs_f64(delay)
+    }
+}
+
+/// Middleware that retries failed requests according to a [`RetryPolicy`]
+///
+/// Requests whose body cannot be cloned (e.g. streaming bodies) are sent once.
+#[derive(Debug, Clone)]
+pub struct Retry {
+    policy: RetryPolicy,
+}
+
+impl Retry {
+    /// Creates a retry middleware for the given policy
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self { policy }
+    }
+}
+
+#[async_trait]
+impl Middleware for Retry {
+    async fn handle(&self, request: reqwest::Request, next: Next<'_>) -> Result<reqwest::Response> {
+        let mut attempt = 1;
+        let mut request = request;
+        loop {
+            let retry_request = if attempt < self.policy.max_attempts {
+                request.try_clone()
+            } else {
+                None
+            };
+
+            match (next.run(request).await, retry_request) {
+                (Err(error), Some(retry_request)) if self.policy.should_retry(&error) => {
+                    tokio::time::sleep(self.policy.backoff(attempt)).await;
+                    request = retry_request;
+                    attempt += 1;
+                }
+                (result, _) => return result,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backoff_without_jitter() {
+        let policy = RetryPolicy::new()
+            .initial_backoff(Duration::from_millis(100))
+            .max_backoff(Duration::from_millis(350))
+            .jitter(false);
+        assert_eq!(policy.backoff(1), Duration::from_millis(100));
+        assert_eq!(policy.backoff(2), Duration::from_millis(200));
+        assert_eq!(policy.backoff(3), Duration::from_millis(350));
+    }
+
+    #[test]
+    fn test_backoff_with_jitter_is_bounded() {
+        let policy = RetryPolicy::new().initial_backoff(Duration::from_millis(100));
+        for retry in 1..5 {
+            assert!(policy.backoff(retry) <= Duration::from_millis(100 * 2u64.pow(retry - 1)));
+        }
+    }
+
+    #[test]
+    fn test_should_retry_statuses() {
+        let policy = RetryPolicy::new();
+        let unavailable = ServiceStackError::from_response_body(503, String::new());
+        let not_found = ServiceStackError::from_response_body(404, String::new());
+        assert!(policy.should_retry(&unavailable));
+        assert!(!policy.should_retry(&not_found));
+        assert!(!policy.should_retry(&ServiceStackError::message("other")));
+
+        let policy = policy.retry_statuses([404]);
+        assert!(policy.should_retry(&not_found));
+        assert!(!policy.should_retry(&unavailable));
+    }
+
+    #[test]
+    fn test_max_attempts_is_at_least_one() {
+        assert_eq!(RetryPolicy::new().max_attempts(0).attempts(), 1);
+    }
+}
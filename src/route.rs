@@ -0,0 +1,314 @@
+//! Route template resolution for request DTOs
+//!
+//! Supports ServiceStack-style route templates such as `/users/{Id}` and
+//! `/files/{Path*}`. Placeholders are filled from the serialized DTO fields,
+//! matching names case-insensitively and ignoring underscores, so `{UserId}`
+//! is filled from a `user_id` field.
+
+use crate::client::PredefinedRoutes;
+use crate::error::{Result, ServiceStackError};
+use crate::query;
+use crate::traits::{HttpMethod, ServiceStackRequest};
+use percent_encoding::{utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+/// Characters that are percent-encoded in a path segment
+const PATH_SEGMENT_ENCODE_SET: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'_')
+    .remove(b'.')
+    .remove(b'~');
+
+/// A route template with its placeholders filled in
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct ResolvedRoute {
+    /// The resolved, percent-encoded path
+    pub path: String,
+    /// The DTO fields that were not used by the route
+    pub fields: Map<String, Value>,
+}
+
+/// A placeholder within a route template
+#[derive(Debug, Clone, PartialEq)]
+struct Placeholder<'a> {
+    name: &'a str,
+    wildcard: bool,
+}
+
+/// Resolves the request path and the DTO fields that remain to be sent
+///
+/// An explicit `path()` is used as-is with the whole DTO, as are the pre-defined
+/// routes for requests without route templates; otherwise the route templates
+/// are filled from the DTO and only the unused fields remain.
+pub(crate) fn resolve_request<T: ServiceStackRequest>(
+    request: &T,
+    predefined_routes: &PredefinedRoutes,
+) -> Result<(String, Value)> {
+    let path = request.path();
+    let payload = serde_json::to_value(request)?;
+    if !path.is_empty() {
+        return Ok((path, payload));
+    }
+    if T::routes().is_empty() {
+        let path = predefined_routes.path(T::operation_name());
+        return Ok((path, payload));
+    }
+
+    let fields = match payload {
+        Value::Object(fields) => fields,
+        Value::Null => Default::default(),
+        _ => {
+            return Err(ServiceStackError::Other(
+                "Request DTO must serialize to a JSON object to use route templates".to_string(),
+            ))
+        }
+    };
+    let resolved = resolve(T::routes(), fields)?;
+    Ok((resolved.path, Value::Object(resolved.fields)))
+}
+
+/// Resolves the URL of a request DTO and the JSON body to send, if any
+///
+/// Shared by the async and blocking clients, see [`request_url`].
+pub(crate) fn resolve_url<T: ServiceStackRequest>(
+    base_url: &str,
+    request: &T,
+    method: &HttpMethod,
+    predefined_routes: &PredefinedRoutes,
+) -> Result<(String, Option<Value>)> {
+    let (path, payload) = resolve_request(request, predefined_routes)?;
+    request_url(base_url, &path, method, Some(payload))
+}
+
+/// Builds the URL of a request to a path and returns the JSON body to send, if any
+///
+/// GET, DELETE, HEAD and OPTIONS requests send the body's fields on the query string
+/// instead of a body.
+pub(crate) fn request_url<B: Serialize>(
+    base_url: &str,
+    path: &str,
+    method: &HttpMethod,
+    body: Option<B>,
+) -> Result<(String, Option<B>)> {
+    let mut url = format!("{}{}", base_url, path);
+    if !sends_query(method) {
+        return Ok((url, body));
+    }
+    if let Some(body) = body {
+        query::append_query(&mut url, &query::to_query_string(&body)?);
+    }
+    Ok((url, None))
+}
+
+/// Whether requests with this method send the DTO on the query string instead of the body
+pub(crate) fn sends_query(method: &HttpMethod) -> bool {
+    !method.has_request_body()
+}
+
+/// Resolves the best matching route template for the given DTO fields
+///
+/// The route with the most placeholders that can all be filled wins, with ties
+/// going to the route declared first. If no route can be filled, the error
+/// names the first missing placeholder of the first route.
+pub(crate) fn resolve(routes: &[&str], fields: Map<String, Value>) -> Result<ResolvedRoute> {
+    let mut best: Option<(&str, usize)> = None;
+    let mut first_error = None;
+
+    for route in routes {
+        let placeholders = placeholders(route)?;
+        match placeholders
+            .iter()
+            .find(|p| find_field(&fields, p.name).is_none())
+        {
+            Some(missing) => {
+                first_error.get_or_insert_with(|| ServiceStackError::MissingRouteParameter {
+                    route: route.to_string(),
+                    name: missing.name.to_string(),
+                });
+            }
+            None => {
+                if !matches!(best, Some((_, count)) if count >= placeholders.len()) {
+                    best = Some((route, placeholders.len()));
+                }
+            }
+        }
+    }
+
+    match best {
+        Some((route, _)) => fill(route, fields),
+        None => Err(first_error
+            .unwrap_or_else(|| ServiceStackError::Other("No route templates defined".to_string()))),
+    }
+}
+
+/// Fills the placeholders of a single route template
+fn fill(route: &str, mut fields: Map<String, Value>) -> Result<ResolvedRoute> {
+    let mut path = String::with_capacity(route.len());
+    let mut rest = route;
+
+    while let Some(start) = rest.find('{') {
+        let end = start + rest[start..].find('}').ok_or_else(|| unclosed(route))?;
+        path.push_str(&rest[..start]);
+
+        let placeholder = parse_placeholder(&rest[start + 1..end]);
+        let key = find_field(&fields, placeholder.name)
+            .ok_or_else(|| ServiceStackError::MissingRouteParameter {
+                route: route.to_string(),
+                name: placeholder.name.to_string(),
+            })?
+            .to_string();
+        let value = fields
+            .get(&key)
+            .and_then(query::query_value)
+            .unwrap_or_default();
+
+        if placeholder.wildcard {
+            let segments: Vec<String> = value
+                .split('/')
+                .map(|s| utf8_percent_encode(s, PATH_SEGMENT_ENCODE_SET).to_string())
+                .collect();
+            path.push_str(&segments.join("/"));
+        } else {
+            path.extend(utf8_percent_encode(&value, PATH_SEGMENT_ENCODE_SET));
+        }
+
+        fields = fields.into_iter().filter(|(k, _)| *k != key).collect();
+        rest = &rest[end + 1..];
+    }
+    path.push_str(rest);
+
+    Ok(ResolvedRoute { path, fields })
+}
+
+/// Returns the placeholders declared in a route template
+fn placeholders(route: &str) -> Result<Vec<Placeholder<'_>>> {
+    let mut result = Vec::new();
+    let mut rest = route;
+    while let Some(start) = rest.find('{') {
+        let end = start + rest[start..].find('}').ok_or_else(|| unclosed(route))?;
+        result.push(parse_placeholder(&rest[start + 1..end]));
+        rest = &rest[end + 1..];
+    }
+    Ok(result)
+}
+
+fn parse_placeholder(text: &str) -> Placeholder<'_> {
+    match text.strip_suffix('*') {
+        Some(name) => Placeholder {
+            name,
+            wildcard: true,
+        },
+        None => Placeholder {
+            name: text,
+            wildcard: false,
+        },
+    }
+}
+
+fn unclosed(route: &str) -> ServiceStackError {
+    ServiceStackError::InvalidUrl(format!("Unclosed placeholder in route '{}'", route))
+}
+
+/// Finds the DTO field that fills a placeholder, ignoring `null` fields
+fn find_field<'a>(fields: &'a Map<String, Value>, name: &str) -> Option<&'a str> {
+    fields
+        .iter()
+        .find(|(key, value)| !value.is_null() && names_match(key, name))
+        .map(|(key, _)| key.as_str())
+}
+
+fn names_match(field: &str, placeholder: &str) -> bool {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| *c != '_')
+            .map(|c| c.to_ascii_lowercase())
+            .collect()
+    };
+    normalize(field) == normalize(placeholder)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn fields(value: Value) -> Map<String, Value> {
+        value.as_object().unwrap().clone()
+    }
+
+    #[test]
+    fn test_sends_query() {
+        assert!(sends_query(&HttpMethod::Get));
+        assert!(sends_query(&HttpMethod::Delete));
+        assert!(sends_query(&HttpMethod::Head));
+        assert!(sends_query(&HttpMethod::Options));
+        assert!(!sends_query(&HttpMethod::Post));
+        assert!(!sends_query(&HttpMethod::Put));
+        assert!(!sends_query(&HttpMethod::Patch));
+        assert!(!sends_query(&HttpMethod::custom("PURGE")));
+    }
+
+    #[test]
+    fn test_request_url() {
+        let body = json!({ "id": 1 });
+        let (url, sent) =
+            request_url("https://a.io", "/users", &HttpMethod::Get, Some(&body)).unwrap();
+        assert_eq!((url.as_str(), sent), ("https://a.io/users?id=1", None));
+        let (url, sent) =
+            request_url("https://a.io", "/users", &HttpMethod::Post, Some(&body)).unwrap();
+        assert_eq!((url.as_str(), sent), ("https://a.io/users", Some(&body)));
+    }
+
+    #[test]
+    fn test_fill_placeholder() {
+        let resolved = resolve(
+            &["/users/{Id}"],
+            fields(json!({ "id": 1, "include_deleted": true })),
+        )
+        .unwrap();
+        assert_eq!(resolved.path, "/users/1");
+        assert_eq!(resolved.fields, fields(json!({ "include_deleted": true })));
+    }
+
+    #[test]
+    fn test_fill_encodes_values() {
+        let resolved = resolve(
+            &["/users/{UserName}/files/{Path*}"],
+            fields(json!({ "user_name": "a/b c", "path": "docs/my file.txt" })),
+        )
+        .unwrap();
+        assert_eq!(resolved.path, "/users/a%2Fb%20c/files/docs/my%20file.txt");
+        assert!(resolved.fields.is_empty());
+    }
+
+    #[test]
+    fn test_resolve_prefers_most_specific_route() {
+        let routes = ["/users", "/users/{Id}"];
+        let resolved = resolve(&routes, fields(json!({ "id": 5 }))).unwrap();
+        assert_eq!(resolved.path, "/users/5");
+
+        let resolved = resolve(&routes, fields(json!({ "id": null }))).unwrap();
+        assert_eq!(resolved.path, "/users");
+    }
+
+    #[test]
+    fn test_missing_placeholder() {
+        let error = resolve(&["/users/{Id}"], fields(json!({ "name": "x" }))).unwrap_err();
+        match error {
+            ServiceStackError::MissingRouteParameter { route, name } => {
+                assert_eq!(route, "/users/{Id}");
+                assert_eq!(name, "Id");
+            }
+            e => panic!("Expected MissingRouteParameter, got {:?}", e),
+        }
+    }
+
+    #[test]
+    fn test_unclosed_placeholder() {
+        assert!(matches!(
+            resolve(&["/users/{Id"], fields(json!({ "id": 1 }))),
+            Err(ServiceStackError::InvalidUrl(_))
+        ));
+    }
+}
@@ -1,47 +1,273 @@
+use crate::error::{Result, ServiceStackError};
 use serde::{de::DeserializeOwned, Serialize};
+use std::str::FromStr;
 
 /// Trait for ServiceStack request DTOs
 ///
 /// Implement this trait on your request types to specify the response type
-/// and the API endpoint path.
+/// and the API endpoint path, either as route templates via [`routes`](Self::routes)
+/// or as a literal path via [`path`](Self::path). Requests with neither are sent to
+/// ServiceStack's pre-defined `/api/{Operation}` or `/json/reply/{Operation}` routes.
 pub trait ServiceStackRequest: Serialize {
     /// The response type for this request
     type Response: ServiceStackResponse;
 
+    /// Returns the ServiceStack operation name for this request type
+    ///
+    /// Used to build the pre-defined routes. Defaults to the Rust type name
+    /// without its module path or generic parameters, e.g. `Hello` for
+    /// `my_app::dtos::Hello`.
+    fn operation_name() -> &'static str {
+        let name = std::any::type_name::<Self>();
+        let name = name.split('<').next().unwrap_or(name);
+        name.rsplit("::").next().unwrap_or(name)
+    }
+
+    /// Returns the ServiceStack route templates for this request type
+    ///
+    /// Example: `&["/users", "/users/{Id}", "/files/{Path*}"]`
+    ///
+    /// Placeholders are filled from the serialized DTO fields (matched
+    /// case-insensitively, ignoring underscores) and the remaining fields are sent
+    /// in the query string or body. When several routes are declared, the route
+    /// with the most placeholders that can all be filled is used.
+    fn routes() -> &'static [&'static str] {
+        &[]
+    }
+
     /// Returns the API endpoint path for this request
     ///
     /// Example: "/hello" or "/users/search"
-    fn path(&self) -> String;
+    ///
+    /// Defaults to an empty path, in which case the path is resolved from
+    /// [`routes`](Self::routes), falling back to the pre-defined routes for
+    /// [`operation_name`](Self::operation_name). A non-empty path takes precedence
+    /// over the route templates and the whole DTO is sent in the query string or body.
+    fn path(&self) -> String {
+        String::new()
+    }
 
     /// Returns the HTTP method for this request (defaults to POST)
     fn method(&self) -> HttpMethod {
         HttpMethod::Post
     }
+
+    /// Returns whether failed requests may be retried by the client's retry policy
+    ///
+    /// Defaults to `None`, which retries only idempotent methods (GET, HEAD, OPTIONS,
+    /// PUT, DELETE).
+    /// Return `Some(true)` to opt a non-idempotent request in, or `Some(false)` to opt out.
+    fn retryable(&self) -> Option<bool> {
+        None
+    }
 }
 
 /// Trait for ServiceStack response DTOs
 ///
 /// Implement this trait on your response types to enable deserialization
-pub trait ServiceStackResponse: DeserializeOwned {}
+pub trait ServiceStackResponse: DeserializeOwned {
+    /// Whether this is a void response, matching ServiceStack's `IReturnVoid`
+    ///
+    /// The body of a void response is never deserialized, so endpoints returning
+    /// `204 No Content` or an empty body succeed.
+    const IS_VOID: bool = false;
+}
+
+/// Void response for operations that return no content (ServiceStack's `IReturnVoid`)
+///
+/// # Example
+///
+/// ```
+/// use serde::Serialize;
+/// use servicestack::ServiceStackRequest;
+///
+/// #[derive(Serialize)]
+/// struct DeleteUser {
+///     id: u32,
+/// }
+///
+/// impl ServiceStackRequest for DeleteUser {
+///     type Response = ();
+/// }
+/// ```
+impl ServiceStackResponse for () {
+    const IS_VOID: bool = true;
+}
 
 /// HTTP methods supported by ServiceStack
-#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub enum HttpMethod {
     Get,
     Post,
     Put,
     Delete,
     Patch,
+    Head,
+    Options,
+    /// Any other verb, e.g. `PROPFIND` or `PURGE`, sent with a JSON body
+    Custom(String),
 }
 
 impl HttpMethod {
-    pub fn as_str(&self) -> &'static str {
+    /// Creates a custom HTTP method, e.g. `HttpMethod::custom("PURGE")`
+    ///
+    /// Standard method names map to their variants, ignoring case. The name is
+    /// validated when the request is sent.
+    pub fn custom(method: impl Into<String>) -> Self {
+        let method = method.into();
+        method.parse().unwrap_or(HttpMethod::Custom(method))
+    }
+
+    /// Returns whether the method is idempotent, so a failed request can be safely retried
+    pub fn is_idempotent(&self) -> bool {
+        matches!(
+            self,
+            HttpMethod::Get
+                | HttpMethod::Put
+                | HttpMethod::Delete
+                | HttpMethod::Head
+                | HttpMethod::Options
+        )
+    }
+
+    /// Returns whether requests with this method send the DTO in a JSON body
+    ///
+    /// GET, DELETE, HEAD and OPTIONS requests send the DTO on the query string instead.
+    pub fn has_request_body(&self) -> bool {
+        !matches!(
+            self,
+            HttpMethod::Get | HttpMethod::Delete | HttpMethod::Head | HttpMethod::Options
+        )
+    }
+
+    pub fn as_str(&self) -> &str {
         match self {
             HttpMethod::Get => "GET",
             HttpMethod::Post => "POST",
             HttpMethod::Put => "PUT",
             HttpMethod::Delete => "DELETE",
             HttpMethod::Patch => "PATCH",
+            HttpMethod::Head => "HEAD",
+            HttpMethod::Options => "OPTIONS",
+            HttpMethod::Custom(method) => method,
         }
     }
 }
+
+impl std::fmt::Display for HttpMethod {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+impl FromStr for HttpMethod {
+    type Err = ServiceStackError;
+
+    /// Parses an HTTP method name
+    ///
+    /// Standard methods are matched ignoring case; any other valid method name
+    /// becomes [`HttpMethod::Custom`] as given.
+    fn from_str(method: &str) -> Result<Self> {
+        match method.to_uppercase().as_str() {
+            "GET" => Ok(HttpMethod::Get),
+            "POST" => Ok(HttpMethod::Post),
+            "PUT" => Ok(HttpMethod::Put),
+            "DELETE" => Ok(HttpMethod::Delete),
+            "PATCH" => Ok(HttpMethod::Patch),
+            "HEAD" => Ok(HttpMethod::Head),
+            "OPTIONS" => Ok(HttpMethod::Options),
+            _ => {
+                reqwest::Method::from_bytes(method.as_bytes())
+                    .map_err(|_| invalid_method(method))?;
+                Ok(HttpMethod::Custom(method.to_string()))
+            }
+        }
+    }
+}
+
+impl TryFrom<&HttpMethod> for reqwest::Method {
+    type Error = ServiceStackError;
+
+    fn try_from(method: &HttpMethod) -> Result<Self> {
+        Ok(match method {
+            HttpMethod::Get => reqwest::Method::GET,
+            HttpMethod::Post => reqwest::Method::POST,
+            HttpMethod::Put => reqwest::Method::PUT,
+            HttpMethod::Delete => reqwest::Method::DELETE,
+            HttpMethod::Patch => reqwest::Method::PATCH,
+            HttpMethod::Head => reqwest::Method::HEAD,
+            HttpMethod::Options => reqwest::Method::OPTIONS,
+            HttpMethod::Custom(method) => reqwest::Method::from_bytes(method.as_bytes())
+                .map_err(|_| invalid_method(method))?,
+        })
+    }
+}
+
+fn invalid_method(method: &str) -> ServiceStackError {
+    ServiceStackError::Other(format!("Invalid HTTP method: {}", method))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::{Deserialize, Serialize};
+
+    #[derive(Serialize)]
+    struct Hello;
+
+    #[derive(Serialize)]
+    struct Wrapper<T>(T);
+
+    #[derive(Deserialize)]
+    struct Empty {}
+
+    impl ServiceStackResponse for Empty {}
+
+    impl ServiceStackRequest for Hello {
+        type Response = Empty;
+    }
+
+    impl<T: Serialize> ServiceStackRequest for Wrapper<T> {
+        type Response = Empty;
+    }
+
+    #[test]
+    fn test_http_method_parsing() {
+        assert_eq!("get".parse::<HttpMethod>().unwrap(), HttpMethod::Get);
+        assert_eq!("PATCH".parse::<HttpMethod>().unwrap(), HttpMethod::Patch);
+        assert_eq!("head".parse::<HttpMethod>().unwrap(), HttpMethod::Head);
+        assert_eq!(
+            "OPTIONS".parse::<HttpMethod>().unwrap(),
+            HttpMethod::Options
+        );
+        assert_eq!(
+            "PROPFIND".parse::<HttpMethod>().unwrap(),
+            HttpMethod::Custom("PROPFIND".to_string())
+        );
+        assert!("BAD VERB".parse::<HttpMethod>().is_err());
+        assert!("".parse::<HttpMethod>().is_err());
+        assert_eq!(HttpMethod::custom("get"), HttpMethod::Get);
+        assert!(HttpMethod::Head.is_idempotent());
+        assert!(!HttpMethod::custom("PURGE").is_idempotent());
+        assert!(HttpMethod::Put.is_idempotent());
+        assert!(!HttpMethod::Post.is_idempotent());
+        assert!(!HttpMethod::Patch.is_idempotent());
+    }
+
+    #[test]
+    fn test_reqwest_method() {
+        let method = reqwest::Method::try_from(&HttpMethod::Options).unwrap();
+        assert_eq!(method, reqwest::Method::OPTIONS);
+        let method = reqwest::Method::try_from(&HttpMethod::custom("PURGE")).unwrap();
+        assert_eq!(method.as_str(), "PURGE");
+        assert!(reqwest::Method::try_from(&HttpMethod::Custom("BAD VERB".to_string())).is_err());
+        assert!(!HttpMethod::Head.has_request_body());
+        assert!(HttpMethod::custom("PURGE").has_request_body());
+    }
+
+    #[test]
+    fn test_default_operation_name() {
+        assert_eq!(Hello::operation_name(), "Hello");
+        assert_eq!(Wrapper::<Hello>::operation_name(), "Wrapper");
+    }
+}
@@ -0,0 +1,216 @@
+//! Multipart file uploads with a request DTO
+//!
+//! Like `PostFileWithRequest`/`PostFilesWithRequest` in ServiceStack's .NET clients,
+//! [`JsonServiceClient::post_file_with_request`](crate::JsonServiceClient::post_file_with_request)
+//! sends one or more files together with the request DTO's fields as `multipart/form-data`
+//! and returns the typed response. Each [`FilePart`] is read from a path, bytes or an
+//! async reader.
+//!
+//! # Example
+//!
+//! ```no_run
+//! # use servicestack::{JsonServiceClient, ServiceStackRequest};
+//! use servicestack::FilePart;
+//!
+//! # async fn run<T: ServiceStackRequest>(client: JsonServiceClient, request: T) -> servicestack::Result<()> {
+//! let files = vec![
+//!     FilePart::from_path("photos/cat.jpg").field_name("photo"),
+//!     FilePart::from_bytes("notes.txt", "Hello").content_type("text/plain"),
+//! ];
+//! let response = client.post_files_with_request(request, files).await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::error::{Result, ServiceStackError};
+use crate::query;
+use bytes::Bytes;
+use reqwest::multipart::{Form, Part};
+use serde_json::Value;
+use std::fmt;
+use std::path::PathBuf;
+use tokio::io::AsyncRead;
+use tokio_util::io::ReaderStream;
+
+/// The field name used for file parts unless another is set
+const DEFAULT_FIELD_NAME: &str = "file";
+
+/// A file sent in a multipart upload
+pub struct FilePart {
+    field_name: String,
+    file_name: Option<String>,
+    content_type: Option<String>,
+    length: Option<u64>,
+    source: Source,
+}
+
+enum Source {
+    Path(PathBuf),
+    Bytes(Bytes),
+    Reader(Box<dyn AsyncRead + Send + Sync + Unpin>),
+}
+
+impl fmt::Debug for FilePart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let source = match &self.source {
+            Source::Path(path) => format!("{:?}", path),
+            Source::Bytes(bytes) => format!("{} bytes", bytes.len()),
+            Source::Reader(_) => "reader".to_string(),
+        };
+        f.debug_struct("FilePart")
+            .field("field_name", &self.field_name)
+            .field("file_name", &self.file_name)
+            .field("content_type", &self.content_type)
+            .field("source", &source)
+            .finish()
+    }
+}
+
+impl FilePart {
+    /// Creates a part read from a file, named after the file
+    ///
+    /// The file is opened when the request is sent.
+    pub fn from_path(path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        let file_name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned());
+        Self::new(file_name, Source::Path(path))
+    }
+
+    /// Creates a part from bytes in memory
+    pub fn from_bytes(file_name: impl Into<String>, bytes: impl Into<Bytes>) -> Self {
+        Self::new(Some(file_name.into()), Source::Bytes(bytes.into()))
+    }
+
+    /// Creates a part streamed from an async reader
+    ///
+    /// Set the [`length`](Self::length) when it is known, otherwise the part is sent
+    /// without a length and the request uses chunked transfer encoding.
+    pub fn from_reader(
+        file_name: impl Into<String>,
+        reader: impl AsyncRead + Send + Sync + Unpin + 'static,
+    ) -> Self {
+        Self::new(Some(file_name.into()), Source::Reader(Box::new(reader)))
+    }
+
+    fn new(file_name: Option<String>, source: Source) -> Self {
+        Self {
+            field_name: DEFAULT_FIELD_NAME.to_string(),
+            file_name,
+            content_type: None,
+            length: None,
+            source,
+        }
+    }
+
+    /// Sets the form field name (defaults to `file`)
+    pub fn field_name(mut self, field_name: impl Into<String>) -> Self {
+        self.field_name = field_name.into();
+        self
+    }
+
+    /// Sets the file name sent to the service
+    pub fn file_name(mut self, file_name: impl Into<String>) -> Self {
+        self.file_name = Some(file_name.into());
+        self
+    }
+
+    /// Sets the content type, which otherwise is guessed from the file name
+    pub fn content_type(mut self, content_type: impl Into<String>) -> Self {
+        self.content_type = Some(content_type.into());
+        self
+    }
+
+    /// Sets the length of a part read from an async reader
+    pub fn length(mut self, length: u64) -> Self {
+        self.length = Some(length);
+        self
+    }
+
+    /// Returns the form field name and the multipart part
+    async fn into_part(self) -> Result<(String, Part)> {
+        let part = match self.source {
+            Source::Path(path) => {
+                let file = tokio::fs::File::open(&path).await?;
+                let length = file.metadata().await?.len();
+                Part::stream_with_length(
+                    reqwest::Body::wrap_stream(ReaderStream::new(file)),
+                    length,
+                )
+            }
+            Source::Bytes(bytes) => Part::stream(bytes),
+            Source::Reader(reader) => {
+                let body = reqwest::Body::wrap_stream(ReaderStream::new(reader));
+                match self.length {
+                    Some(length) => Part::stream_with_length(body, length),
+                    None => Part::stream(body),
+                }
+            }
+        };
+
+        let content_type = self.content_type.or_else(|| {
+            let file_name = self.file_name.as_deref()?;
+            mime_guess::from_path(file_name)
+                .first()
+                .map(|mime| mime.to_string())
+        });
+        let part = match content_type {
+            Some(content_type) => part
+                .mime_str(&content_type)
+                .map_err(|e| ServiceStackError::InvalidHeader(format!("Content-Type: {}", e)))?,
+            None => part,
+        };
+        let part = match self.file_name {
+            Some(file_name) => part.file_name(file_name),
+            None => part,
+        };
+        Ok((self.field_name, part))
+    }
+}
+
+/// Builds a multipart form from the remaining DTO fields and the file parts
+pub(crate) async fn form(payload: &Value, files: Vec<FilePart>) -> Result<Form> {
+    let fields = match payload {
+        Value::Object(fields) => query::field_pairs(fields),
+        Value::Null => Vec::new(),
+        _ => {
+            return Err(ServiceStackError::Other(
+                "Request DTO must serialize to a JSON object to be sent as form fields".to_string(),
+            ))
+        }
+    };
+
+    let mut form = Form::new();
+    for (name, value) in fields {
+        form = form.text(name, value);
+    }
+    for file in files {
+        let (name, part) = file.into_part().await?;
+        form = form.part(name, part);
+    }
+    Ok(form)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_path_uses_file_name() {
+        let part = FilePart::from_path("uploads/cat.jpg");
+        assert_eq!(part.field_name, "file");
+        assert_eq!(part.file_name.as_deref(), Some("cat.jpg"));
+
+        let part = part.field_name("photo").file_name("kitten.jpg");
+        assert_eq!(part.field_name, "photo");
+        assert_eq!(part.file_name.as_deref(), Some("kitten.jpg"));
+    }
+
+    #[tokio::test]
+    async fn test_invalid_content_type() {
+        let part = FilePart::from_bytes("a.txt", "a").content_type("not a mime\n");
+        let result = part.into_part().await;
+        assert!(matches!(result, Err(ServiceStackError::InvalidHeader(_))));
+    }
+}
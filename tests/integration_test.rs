@@ -1,6 +1,9 @@
-use mockito::{Server, ServerGuard};
+use mockito::{Matcher, Server};
 use serde::{Deserialize, Serialize};
-use servicestack::{JsonServiceClient, ServiceStackRequest, ServiceStackResponse};
+use servicestack::{
+    JsonServiceClient, PredefinedRoutes, RequestOptions, ServiceStackClient, ServiceStackRequest,
+    ServiceStackResponse,
+};
 
 #[derive(Serialize, Debug)]
 struct HelloRequest {
@@ -48,6 +51,7 @@ async fn test_get_request() {
     let mut server = Server::new_async().await;
     let mock = server
         .mock("GET", "/hello")
+        .match_query(Matcher::UrlEncoded("name".into(), "World".into()))
         .with_status(200)
         .with_header("content-type", "application/json")
         .with_body(r#"{"result":"Hello, World!"}"#)
@@ -108,9 +112,14 @@ async fn test_api_error_handling() {
 
     if let Err(e) = result {
         match e {
-            servicestack::ServiceStackError::ApiError { status, message } => {
+            servicestack::ServiceStackError::ApiError {
+                status,
+                message,
+                response_status,
+            } => {
                 assert_eq!(status, 404);
                 assert_eq!(message, "Not Found");
+                assert!(response_status.is_none());
             }
             _ => panic!("Expected ApiError"),
         }
@@ -200,3 +209,2529 @@ async fn test_raw_request_method() {
     assert_eq!(response.result, "Custom response");
     mock.assert_async().await;
 }
+
+#[derive(Serialize, Debug)]
+struct FindUsersRequest {
+    user_ids: Vec<u32>,
+    include_deleted: bool,
+    name: Option<String>,
+}
+
+impl ServiceStackRequest for FindUsersRequest {
+    type Response = SearchResponse;
+
+    fn path(&self) -> String {
+        "/users".to_string()
+    }
+
+    fn method(&self) -> servicestack::HttpMethod {
+        servicestack::HttpMethod::Get
+    }
+}
+
+#[tokio::test]
+async fn test_get_request_serializes_query_string() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/users")
+        .match_query(Matcher::Exact(
+            "userIds=1%2C2%2C3&includeDeleted=true".to_string(),
+        ))
+        .match_body(Matcher::Exact(String::new()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"results":["alice"]}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = FindUsersRequest {
+        user_ids: vec![1, 2, 3],
+        include_deleted: true,
+        name: None,
+    };
+
+    let response = client.send(request).await.unwrap();
+    assert_eq!(response.results, vec!["alice"]);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_delete_request_serializes_query_string() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/hello")
+        .match_query(Matcher::UrlEncoded("name".into(), "Jane Doe".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Deleted"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = HelloRequest {
+        name: "Jane Doe".to_string(),
+    };
+
+    let response = client.delete(request).await.unwrap();
+    assert_eq!(response.result, "Deleted");
+    mock.assert_async().await;
+}
+
+#[derive(Serialize, Debug)]
+struct GetUserRequest {
+    id: u32,
+    include_deleted: bool,
+}
+
+impl ServiceStackRequest for GetUserRequest {
+    type Response = HelloResponse;
+
+    fn routes() -> &'static [&'static str] {
+        &["/users/{Id}"]
+    }
+
+    fn method(&self) -> servicestack::HttpMethod {
+        servicestack::HttpMethod::Get
+    }
+}
+
+#[derive(Serialize, Debug)]
+struct UpdateFileRequest {
+    path: String,
+    content: String,
+}
+
+impl ServiceStackRequest for UpdateFileRequest {
+    type Response = HelloResponse;
+
+    fn routes() -> &'static [&'static str] {
+        &["/files/{Path*}"]
+    }
+}
+
+#[tokio::test]
+async fn test_route_template_with_query_string() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/users/42")
+        .match_query(Matcher::Exact("includeDeleted=true".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"User 42"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = GetUserRequest {
+        id: 42,
+        include_deleted: true,
+    };
+
+    let response = client.send(request).await.unwrap();
+    assert_eq!(response.result, "User 42");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_wildcard_route_sends_remaining_fields_in_body() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/files/docs/read%20me.txt")
+        .match_body(Matcher::Json(serde_json::json!({ "content": "hi" })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Saved"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = UpdateFileRequest {
+        path: "docs/read me.txt".to_string(),
+        content: "hi".to_string(),
+    };
+
+    let response = client.send(request).await.unwrap();
+    assert_eq!(response.result, "Saved");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_missing_route_parameter_fails_before_sending() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", Matcher::Any)
+        .expect(0)
+        .create_async()
+        .await;
+
+    #[derive(Serialize)]
+    struct NoIdRequest {
+        id: Option<u32>,
+    }
+
+    impl ServiceStackRequest for NoIdRequest {
+        type Response = HelloResponse;
+
+        fn routes() -> &'static [&'static str] {
+            &["/users/{Id}"]
+        }
+    }
+
+    let client = JsonServiceClient::new(server.url());
+    match client.send(NoIdRequest { id: None }).await {
+        Err(servicestack::ServiceStackError::MissingRouteParameter { route, name }) => {
+            assert_eq!(route, "/users/{Id}");
+            assert_eq!(name, "Id");
+        }
+        other => panic!("Expected MissingRouteParameter, got {:?}", other.err()),
+    }
+    mock.assert_async().await;
+}
+
+#[derive(Serialize, Debug)]
+struct Hello {
+    name: String,
+}
+
+impl ServiceStackRequest for Hello {
+    type Response = HelloResponse;
+}
+
+#[tokio::test]
+async fn test_predefined_api_route() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/Hello")
+        .match_body(Matcher::Json(serde_json::json!({ "name": "World" })))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Hello, World!"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = Hello {
+        name: "World".to_string(),
+    };
+
+    let response = client.send(request).await.unwrap();
+    assert_eq!(response.result, "Hello, World!");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_predefined_json_reply_route() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/json/reply/Hello")
+        .match_query(Matcher::UrlEncoded("name".into(), "World".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Hello, World!"}"#)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_predefined_routes(PredefinedRoutes::JsonReply);
+    let request = Hello {
+        name: "World".to_string(),
+    };
+
+    let response = client.get(request).await.unwrap();
+    assert_eq!(response.result, "Hello, World!");
+    mock.assert_async().await;
+}
+
+#[derive(Serialize, Debug)]
+struct DeleteUser {
+    id: u32,
+}
+
+impl ServiceStackRequest for DeleteUser {
+    type Response = ();
+
+    fn routes() -> &'static [&'static str] {
+        &["/users/{Id}"]
+    }
+
+    fn method(&self) -> servicestack::HttpMethod {
+        servicestack::HttpMethod::Delete
+    }
+}
+
+#[tokio::test]
+async fn test_void_response_with_no_content() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/users/7")
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    client.send(DeleteUser { id: 7 }).await.unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_void_response_ignores_body() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/users/7")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    client.send(DeleteUser { id: 7 }).await.unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_empty_body_for_raw_and_untyped_requests() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/reset")
+        .with_status(204)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    client
+        .request::<serde_json::Value, ()>("POST", "/reset", None)
+        .await
+        .unwrap();
+
+    let client = ServiceStackClient::new(server.url());
+    client
+        .post::<_, ()>("/reset", &serde_json::json!({}))
+        .await
+        .unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_void_raw_and_untyped_requests_ignore_body() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/reset")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body("{}")
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    client
+        .request::<serde_json::Value, ()>("GET", "/reset", None)
+        .await
+        .unwrap();
+
+    let client = ServiceStackClient::new(server.url());
+    client.get::<()>("/reset").await.unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_response_status_error_parsing() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"responseStatus":{"errorCode":"ArgumentException","message":"Name is required","stackTrace":"at Hello","errors":[{"errorCode":"NotEmpty","fieldName":"Name","message":"'Name' must not be empty."}],"meta":{"traceId":"t1"}}}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = HelloRequest {
+        name: String::new(),
+    };
+
+    let error = client.post(request).await.unwrap_err();
+    assert_eq!(error.to_string(), "API error: 400 - Name is required");
+    assert_eq!(error.error_code(), Some("ArgumentException"));
+
+    let status = error.response_status().unwrap();
+    assert_eq!(status.stack_trace.as_deref(), Some("at Hello"));
+    assert_eq!(status.errors[0].field_name.as_deref(), Some("Name"));
+    assert_eq!(status.meta.as_ref().unwrap()["traceId"], "t1");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_servicestack_client_response_status_error() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"responseStatus":{"errorCode":"ValidationException","message":"Validation failed","errors":[{"errorCode":"NotEmpty","fieldName":"Name","message":"'Name' must not be empty."}]}}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = ServiceStackClient::new(server.url());
+    let error = client
+        .post::<_, HelloResponse>("/hello", &serde_json::json!({ "name": "" }))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status_code(), Some(400));
+    assert_eq!(error.error_code(), Some("ValidationException"));
+    assert_eq!(
+        error.field_error_message("Name"),
+        Some("'Name' must not be empty.")
+    );
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_builder_configuration() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/v1/Hello")
+        .match_header("User-Agent", "integration-test/1.0")
+        .match_header("X-Tenant", "acme")
+        .match_header("Authorization", "Bearer builder-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Configured"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .timeout(std::time::Duration::from_secs(5))
+        .user_agent("integration-test/1.0")
+        .default_header("X-Tenant", "acme")
+        .bearer_token("builder-token")
+        .base_path("/v1")
+        .build()
+        .unwrap();
+
+    let request = Hello {
+        name: "World".to_string(),
+    };
+    let response = client.send(request).await.unwrap();
+    assert_eq!(response.result, "Configured");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_and_response_filters() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_header("X-Tenant", "acme")
+        .match_query(Matcher::UrlEncoded("debug".into(), "true".into()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("X-Request-Id", "req-1")
+        .with_body(r#"{"result":"Filtered"}"#)
+        .create_async()
+        .await;
+
+    let captured = std::sync::Arc::new(std::sync::Mutex::new(None));
+    let captured_in_filter = captured.clone();
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.add_request_filter(|request: &mut reqwest::Request| {
+        request.headers_mut().insert(
+            "X-Tenant",
+            reqwest::header::HeaderValue::from_static("acme"),
+        );
+        request.url_mut().set_query(Some("debug=true"));
+    });
+    client.add_response_filter(move |response: &reqwest::Response| {
+        let request_id = response
+            .headers()
+            .get("X-Request-Id")
+            .map(|v| v.to_str().unwrap().to_string());
+        *captured_in_filter.lock().unwrap() = request_id;
+    });
+
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let response = client.post(request).await.unwrap();
+    assert_eq!(response.result, "Filtered");
+    assert_eq!(captured.lock().unwrap().as_deref(), Some("req-1"));
+    mock.assert_async().await;
+}
+
+struct AsyncTenantFilter;
+
+#[async_trait::async_trait]
+impl servicestack::RequestFilter for AsyncTenantFilter {
+    async fn filter(&self, request: &mut reqwest::Request) -> servicestack::Result<()> {
+        let tenant = tokio::task::spawn_blocking(|| "async-tenant")
+            .await
+            .unwrap();
+        request.headers_mut().insert(
+            "X-Tenant",
+            reqwest::header::HeaderValue::from_static(tenant),
+        );
+        *request.body_mut() = Some(reqwest::Body::from(r#"{"name":"Changed"}"#));
+        Ok(())
+    }
+}
+
+struct RejectServerErrors;
+
+#[async_trait::async_trait]
+impl servicestack::ResponseFilter for RejectServerErrors {
+    async fn filter(&self, response: &reqwest::Response) -> servicestack::Result<()> {
+        if response.status().is_server_error() {
+            return Err(servicestack::ServiceStackError::message(
+                "server unavailable",
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_async_filters() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_header("X-Tenant", "async-tenant")
+        .match_body(Matcher::Json(serde_json::json!({ "name": "Changed" })))
+        .with_status(503)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .request_filter(AsyncTenantFilter)
+        .response_filter(RejectServerErrors)
+        .build()
+        .unwrap();
+
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let error = client.post(request).await.unwrap_err();
+    assert_eq!(error.to_string(), "Error: server unavailable");
+    mock.assert_async().await;
+}
+
+struct RecordOrder {
+    name: &'static str,
+    log: std::sync::Arc<std::sync::Mutex<Vec<String>>>,
+}
+
+#[async_trait::async_trait]
+impl servicestack::Middleware for RecordOrder {
+    async fn handle(
+        &self,
+        request: reqwest::Request,
+        next: servicestack::Next<'_>,
+    ) -> servicestack::Result<reqwest::Response> {
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("before {}", self.name));
+        let result = next.run(request).await;
+        self.log
+            .lock()
+            .unwrap()
+            .push(format!("after {}", self.name));
+        result
+    }
+}
+
+#[tokio::test]
+async fn test_middleware_runs_in_order_around_built_ins() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_header("Authorization", "Bearer token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"ok"}"#)
+        .create_async()
+        .await;
+
+    let log = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let filter_log = log.clone();
+    let client = JsonServiceClient::builder(server.url())
+        .bearer_token("token")
+        .middleware(RecordOrder {
+            name: "outer",
+            log: log.clone(),
+        })
+        .middleware(RecordOrder {
+            name: "inner",
+            log: log.clone(),
+        })
+        .request_filter(move |request: &mut reqwest::Request| {
+            assert!(request.headers().contains_key("Authorization"));
+            filter_log.lock().unwrap().push("filter".to_string());
+        })
+        .build()
+        .unwrap();
+
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    client.post(request).await.unwrap();
+    assert_eq!(
+        *log.lock().unwrap(),
+        vec![
+            "before outer",
+            "before inner",
+            "filter",
+            "after inner",
+            "after outer"
+        ]
+    );
+    mock.assert_async().await;
+}
+
+struct RetryOnce;
+
+#[async_trait::async_trait]
+impl servicestack::Middleware for RetryOnce {
+    async fn handle(
+        &self,
+        request: reqwest::Request,
+        next: servicestack::Next<'_>,
+    ) -> servicestack::Result<reqwest::Response> {
+        let retry = request.try_clone().unwrap();
+        match next.run(request).await {
+            Err(e) if e.status_code() == Some(503) => next.run(retry).await,
+            result => result,
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_middleware_can_retry_mapped_errors() {
+    let mut server = Server::new_async().await;
+    let unavailable = server
+        .mock("POST", "/hello")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.add_middleware(RetryOnce);
+
+    let succeeded = server
+        .mock("POST", "/hello")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Retried"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let response = client.post(request).await.unwrap();
+    assert_eq!(response.result, "Retried");
+    unavailable.assert_async().await;
+    succeeded.assert_async().await;
+}
+
+struct ShortCircuit;
+
+#[async_trait::async_trait]
+impl servicestack::Middleware for ShortCircuit {
+    async fn handle(
+        &self,
+        _request: reqwest::Request,
+        _next: servicestack::Next<'_>,
+    ) -> servicestack::Result<reqwest::Response> {
+        let response = http::Response::builder()
+            .status(200)
+            .body(r#"{"result":"From middleware"}"#)
+            .unwrap();
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+#[tokio::test]
+async fn test_middleware_can_short_circuit() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", Matcher::Any)
+        .expect(0)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.add_middleware(ShortCircuit);
+
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let response = client.post(request).await.unwrap();
+    assert_eq!(response.result, "From middleware");
+    mock.assert_async().await;
+}
+
+fn fast_retry_policy() -> servicestack::RetryPolicy {
+    servicestack::RetryPolicy::new()
+        .max_attempts(3)
+        .initial_backoff(std::time::Duration::from_millis(1))
+}
+
+#[tokio::test]
+async fn test_retry_policy_retries_idempotent_requests() {
+    let mut server = Server::new_async().await;
+    let unavailable = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .with_status(503)
+        .expect(2)
+        .create_async()
+        .await;
+    let client = JsonServiceClient::builder(server.url())
+        .retry_policy(fast_retry_policy())
+        .build()
+        .unwrap();
+    let succeeded = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"User 1"}"#)
+        .create_async()
+        .await;
+
+    let request = GetUserRequest {
+        id: 1,
+        include_deleted: false,
+    };
+    let response = client.send(request).await.unwrap();
+    assert_eq!(response.result, "User 1");
+    unavailable.assert_async().await;
+    succeeded.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_policy_gives_up_after_max_attempts() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/users/7")
+        .with_status(504)
+        .expect(3)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_retry_policy(fast_retry_policy());
+
+    let error = client.send(DeleteUser { id: 7 }).await.unwrap_err();
+    assert_eq!(error.status_code(), Some(504));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_retry_policy_skips_non_idempotent_requests() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .with_status(503)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_retry_policy(fast_retry_policy());
+
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let error = client.post(request).await.unwrap_err();
+    assert_eq!(error.status_code(), Some(503));
+    mock.assert_async().await;
+}
+
+#[derive(Serialize, Debug)]
+struct RetryableHello {
+    name: String,
+}
+
+impl ServiceStackRequest for RetryableHello {
+    type Response = HelloResponse;
+
+    fn path(&self) -> String {
+        "/hello".to_string()
+    }
+
+    fn retryable(&self) -> Option<bool> {
+        Some(true)
+    }
+}
+
+#[tokio::test]
+async fn test_retry_policy_request_opt_in() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .with_status(502)
+        .expect(3)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_retry_policy(fast_retry_policy());
+
+    let request = RetryableHello {
+        name: "World".to_string(),
+    };
+    assert!(client.send(request).await.is_err());
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_fails_fast_when_open() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/users/7")
+        .with_status(503)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .circuit_breaker(
+            servicestack::CircuitBreaker::new()
+                .minimum_requests(2)
+                .open_duration(std::time::Duration::from_secs(60)),
+        )
+        .build()
+        .unwrap();
+
+    for _ in 0..2 {
+        let error = client.send(DeleteUser { id: 7 }).await.unwrap_err();
+        assert_eq!(error.status_code(), Some(503));
+    }
+    assert_eq!(
+        client.circuit_state(),
+        Some(servicestack::CircuitState::Open)
+    );
+
+    let error = client.send(DeleteUser { id: 7 }).await.unwrap_err();
+    assert!(matches!(
+        error,
+        servicestack::ServiceStackError::CircuitOpen { .. }
+    ));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_circuit_breaker_shared_by_clones() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("DELETE", "/users/7")
+        .with_status(503)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let breaker = servicestack::CircuitBreaker::new()
+        .minimum_requests(2)
+        .open_duration(std::time::Duration::from_secs(60));
+    let first = JsonServiceClient::builder(server.url())
+        .circuit_breaker(breaker.clone())
+        .build()
+        .unwrap();
+    let mut second = JsonServiceClient::new(server.url());
+    second.set_circuit_breaker(breaker.clone());
+    let mut separate = JsonServiceClient::new(server.url());
+    separate.set_circuit_breaker(servicestack::CircuitBreaker::new());
+
+    for _ in 0..2 {
+        assert!(first.send(DeleteUser { id: 7 }).await.is_err());
+    }
+    assert_eq!(breaker.state(), servicestack::CircuitState::Open);
+    let error = second.send(DeleteUser { id: 7 }).await.unwrap_err();
+    assert!(matches!(
+        error,
+        servicestack::ServiceStackError::CircuitOpen { .. }
+    ));
+    assert_eq!(
+        separate.circuit_state(),
+        Some(servicestack::CircuitState::Closed)
+    );
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_with_request_options() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/users/42")
+        .match_query(Matcher::Exact("includeDeleted=true&trace=on".to_string()))
+        .match_header("X-Request-Id", "42")
+        .match_header("Authorization", "Bearer override-token")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"User 42"}"#)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_bearer_token("client-token");
+
+    let request = GetUserRequest {
+        id: 42,
+        include_deleted: true,
+    };
+    let options = RequestOptions::new()
+        .header("X-Request-Id", "42")
+        .query("trace", "on")
+        .bearer_token("override-token")
+        .timeout(std::time::Duration::from_secs(5));
+
+    let response = client.send_with(request, options).await.unwrap();
+    assert_eq!(response.result, "User 42");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_with_invalid_header() {
+    let client = JsonServiceClient::new("http://localhost");
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let options = RequestOptions::new().header("X-Bad", "bad\nvalue");
+
+    let error = client.send_with(request, options).await.unwrap_err();
+    assert!(matches!(
+        error,
+        servicestack::ServiceStackError::InvalidHeader(_)
+    ));
+}
+
+#[tokio::test]
+async fn test_servicestack_client_request_options() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_query(Matcher::Exact("debug=true".to_string()))
+        .match_header("X-Tenant", "acme")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Hello, World!"}"#)
+        .create_async()
+        .await;
+
+    let client = ServiceStackClient::new(server.url());
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let options = RequestOptions::new()
+        .header("X-Tenant", "acme")
+        .query("debug", true);
+
+    let response: HelloResponse = client.post_with("/hello", &request, options).await.unwrap();
+    assert_eq!(response.result, "Hello, World!");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_head_request_returns_headers() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("HEAD", "/users/42")
+        .match_query(Matcher::Exact("includeDeleted=true".to_string()))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("content-length", "19")
+        .with_header("etag", "\"v1\"")
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = GetUserRequest {
+        id: 42,
+        include_deleted: true,
+    };
+
+    let response = client.head(request).await.unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.content_type(), Some("application/json"));
+    assert_eq!(response.content_length(), Some(19));
+    assert_eq!(response.etag(), Some("\"v1\""));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_servicestack_client_head() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("HEAD", "/hello")
+        .with_status(200)
+        .with_header("x-version", "2")
+        .create_async()
+        .await;
+
+    let client = ServiceStackClient::new(server.url());
+    let response = client.head("/hello").await.unwrap();
+    assert_eq!(response.header("X-Version"), Some("2"));
+    mock.assert_async().await;
+}
+
+#[derive(Serialize, Debug)]
+struct PurgeCache {
+    key: String,
+}
+
+impl ServiceStackRequest for PurgeCache {
+    type Response = ();
+
+    fn path(&self) -> String {
+        "/cache".to_string()
+    }
+
+    fn method(&self) -> servicestack::HttpMethod {
+        servicestack::HttpMethod::custom("PURGE")
+    }
+}
+
+#[tokio::test]
+async fn test_custom_http_verb() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("PURGE", "/cache")
+        .match_body(Matcher::Json(serde_json::json!({"key": "users"})))
+        .with_status(204)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = PurgeCache {
+        key: "users".to_string(),
+    };
+    client.send(request).await.unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_raw_request_with_options_method() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("OPTIONS", "/hello")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"GET, POST"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let response: HelloResponse = client
+        .request::<(), _>("OPTIONS", "/hello", None)
+        .await
+        .unwrap();
+    assert_eq!(response.result, "GET, POST");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_with_metadata() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/users/42")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_header("x-total-count", "100")
+        .with_header("set-cookie", "a=1")
+        .with_header("set-cookie", "b=2")
+        .with_body(r#"{"result":"User 42"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = GetUserRequest {
+        id: 42,
+        include_deleted: false,
+    };
+
+    let response = client
+        .send_with_metadata(request, RequestOptions::default())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), 200);
+    assert_eq!(response.header("X-Total-Count"), Some("100"));
+    assert_eq!(response.header_values("Set-Cookie"), vec!["a=1", "b=2"]);
+    assert_eq!(response.url().path(), "/users/42");
+    assert_eq!(response.into_body().result, "User 42");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_api_returns_validation_errors() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_body(
+            r#"{"responseStatus":{"errorCode":"NotEmpty","message":"'Name' must not be empty.",
+            "errors":[{"errorCode":"NotEmpty","fieldName":"Name","message":"'Name' must not be empty."}]}}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = HelloRequest {
+        name: String::new(),
+    };
+
+    let result = client.api(request).await;
+    assert!(!result.succeeded());
+    assert_eq!(result.status_code(), Some(400));
+    assert_eq!(
+        result
+            .field_error("name")
+            .and_then(|e| e.error_code.as_deref()),
+        Some("NotEmpty")
+    );
+    assert_eq!(result.summary_message(), Some("'Name' must not be empty."));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_api_turns_transport_failures_into_response_status() {
+    // Nothing listens on port 9 (discard), so the connection is refused
+    let client = JsonServiceClient::new("http://127.0.0.1:9");
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+
+    let result = client.api(request).await;
+    assert!(result.failed());
+    assert_eq!(result.status_code(), None);
+    assert_eq!(result.error_code(), Some("ConnectionError"));
+    assert!(result.summary_message().is_some());
+}
+
+#[tokio::test]
+async fn test_api_success() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"{"result":"Hello, World!"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+
+    let result = client.api(request).await;
+    assert!(result.succeeded());
+    assert!(result.error().is_none());
+    assert_eq!(result.into_response().unwrap().result, "Hello, World!");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_all_batches_requests_in_order() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/json/reply/HelloRequest[]")
+        .match_body(Matcher::Json(serde_json::json!([
+            {"name": "A"},
+            {"name": "B"},
+            {"name": "C"}
+        ])))
+        .with_status(200)
+        .with_header("content-type", "application/json")
+        .with_body(r#"[{"result":"Hello, A!"},{"result":"Hello, B!"},{"result":"Hello, C!"}]"#)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_predefined_routes(PredefinedRoutes::JsonReply);
+    let requests = ["A", "B", "C"]
+        .into_iter()
+        .map(|name| HelloRequest {
+            name: name.to_string(),
+        })
+        .collect();
+
+    let responses = client.send_all(requests).await.unwrap();
+    let results: Vec<_> = responses.into_iter().map(|r| r.result).collect();
+    assert_eq!(results, ["Hello, A!", "Hello, B!", "Hello, C!"]);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_all_reports_failed_item() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/api/HelloRequest[]")
+        .with_status(400)
+        .with_header("content-type", "application/json")
+        .with_header("X-AutoBatch-Completed", "1")
+        .with_body(
+            r#"{"responseStatus":{"errorCode":"NotEmpty","message":"'Name' must not be empty."}}"#,
+        )
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let requests = vec![
+        HelloRequest {
+            name: "A".to_string(),
+        },
+        HelloRequest {
+            name: String::new(),
+        },
+    ];
+
+    let error = client.send_all(requests).await.unwrap_err();
+    assert!(matches!(
+        error,
+        servicestack::ServiceStackError::BatchError { index: Some(1), .. }
+    ));
+    assert_eq!(error.status_code(), Some(400));
+    assert_eq!(error.error_code(), Some("NotEmpty"));
+    assert_eq!(
+        error.to_string(),
+        "Batch request failed at item 1: API error: 400 - 'Name' must not be empty."
+    );
+    mock.assert_async().await;
+}
+
+/// Echoes the request name after a delay, tracking the peak number of requests in flight
+#[derive(Clone, Default)]
+struct ConcurrencyProbe {
+    in_flight: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+    peak: std::sync::Arc<std::sync::atomic::AtomicUsize>,
+}
+
+#[async_trait::async_trait]
+impl servicestack::Middleware for ConcurrencyProbe {
+    async fn handle(
+        &self,
+        request: reqwest::Request,
+        _next: servicestack::Next<'_>,
+    ) -> servicestack::Result<reqwest::Response> {
+        use std::sync::atomic::Ordering;
+
+        let body: serde_json::Value =
+            serde_json::from_slice(request.body().unwrap().as_bytes().unwrap()).unwrap();
+        let name = body["name"].as_str().unwrap().to_string();
+
+        let in_flight = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.peak.fetch_max(in_flight, Ordering::SeqCst);
+        // Later requests finish first, so completion order differs from input order
+        let delay = 30 - 5 * name.len().min(5) as u64;
+        tokio::time::sleep(std::time::Duration::from_millis(delay)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+
+        if name.starts_with("fail") {
+            return Err(servicestack::ServiceStackError::message(name));
+        }
+        let response = http::Response::builder()
+            .status(200)
+            .body(format!(r#"{{"result":"{}"}}"#, name))
+            .unwrap();
+        Ok(reqwest::Response::from(response))
+    }
+}
+
+fn hello_requests(names: &[&str]) -> Vec<HelloRequest> {
+    names
+        .iter()
+        .map(|name| HelloRequest {
+            name: name.to_string(),
+        })
+        .collect()
+}
+
+#[tokio::test]
+async fn test_send_concurrent_keeps_input_order_and_limit() {
+    let probe = ConcurrencyProbe::default();
+    let mut client = JsonServiceClient::new("http://localhost");
+    client.add_middleware(probe.clone());
+
+    let names = ["a", "bb", "ccc", "dddd", "eeeee", "f"];
+    let responses = client
+        .send_concurrent(hello_requests(&names), 2)
+        .await
+        .unwrap();
+
+    let results: Vec<_> = responses.into_iter().map(|r| r.result).collect();
+    assert_eq!(results, names);
+    assert_eq!(probe.peak.load(std::sync::atomic::Ordering::SeqCst), 2);
+}
+
+#[tokio::test]
+async fn test_send_concurrent_fails_fast() {
+    let mut client = JsonServiceClient::new("http://localhost");
+    client.add_middleware(ConcurrencyProbe::default());
+
+    let error = client
+        .send_concurrent(hello_requests(&["a", "fail", "c"]), 3)
+        .await
+        .unwrap_err();
+    assert_eq!(error.to_string(), "Error: fail");
+}
+
+#[tokio::test]
+async fn test_send_concurrent_all_collects_every_result() {
+    let mut client = JsonServiceClient::new("http://localhost");
+    client.add_middleware(ConcurrencyProbe::default());
+
+    let results = client
+        .send_concurrent_all(hello_requests(&["a", "fail", "c"]), 2)
+        .await;
+    assert_eq!(results.len(), 3);
+    assert_eq!(results[0].as_ref().unwrap().result, "a");
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap().result, "c");
+}
+
+#[tokio::test]
+async fn test_send_concurrent_stream_yields_indexes() {
+    use futures_util::StreamExt;
+
+    let mut client = JsonServiceClient::new("http://localhost");
+    client.add_middleware(ConcurrencyProbe::default());
+
+    let requests = hello_requests(&["a", "bb", "ccc"]);
+    let mut results: Vec<_> = client
+        .send_concurrent_stream(requests, 3)
+        .map(|(index, result)| (index, result.unwrap().result))
+        .collect()
+        .await;
+    // The shortest delay finishes first
+    assert_eq!(results[0], (2, "ccc".to_string()));
+    results.sort();
+    assert_eq!(
+        results,
+        [
+            (0, "a".to_string()),
+            (1, "bb".to_string()),
+            (2, "ccc".to_string())
+        ]
+    );
+}
+
+#[derive(Serialize, Debug)]
+struct GetFile {
+    path: String,
+}
+
+impl ServiceStackRequest for GetFile {
+    type Response = ();
+
+    fn routes() -> &'static [&'static str] {
+        &["/files/{Path*}"]
+    }
+
+    fn method(&self) -> servicestack::HttpMethod {
+        servicestack::HttpMethod::Get
+    }
+}
+
+#[tokio::test]
+async fn test_download_stream_with_progress() {
+    use futures_util::StreamExt;
+
+    let body = vec![7u8; 64 * 1024];
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/files/docs/report.bin")
+        .with_status(200)
+        .with_header("content-type", "application/octet-stream")
+        .with_header(
+            "content-disposition",
+            r#"attachment; filename="report.bin""#,
+        )
+        .with_body(&body)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = GetFile {
+        path: "docs/report.bin".to_string(),
+    };
+    let download = client.download(request).await.unwrap();
+    assert_eq!(download.content_type(), Some("application/octet-stream"));
+    assert_eq!(download.content_length(), Some(body.len() as u64));
+    assert_eq!(download.file_name().as_deref(), Some("report.bin"));
+
+    let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let reported = progress.clone();
+    let mut chunks = std::pin::pin!(download
+        .on_progress(move |p| reported.lock().unwrap().push(p))
+        .bytes_stream());
+    let mut received = Vec::new();
+    while let Some(chunk) = chunks.next().await {
+        received.extend_from_slice(&chunk.unwrap());
+    }
+
+    assert_eq!(received, body);
+    let last = *progress.lock().unwrap().last().unwrap();
+    assert_eq!(last.transferred, body.len() as u64);
+    assert_eq!(last.fraction(), Some(1.0));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_download_save_to_file() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/files/notes.txt")
+        .with_status(200)
+        .with_header("content-type", "text/plain")
+        .with_body("hello file")
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let path =
+        std::env::temp_dir().join(format!("servicestack-download-{}.txt", std::process::id()));
+    let request = GetFile {
+        path: "notes.txt".to_string(),
+    };
+    let written = client
+        .download(request)
+        .await
+        .unwrap()
+        .save_to(&path)
+        .await
+        .unwrap();
+
+    assert_eq!(written, 10);
+    assert_eq!(std::fs::read_to_string(&path).unwrap(), "hello file");
+    std::fs::remove_file(&path).unwrap();
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_download_error_response() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/files/missing.txt")
+        .with_status(404)
+        .with_body(r#"{"responseStatus":{"errorCode":"NotFound","message":"File not found"}}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = GetFile {
+        path: "missing.txt".to_string(),
+    };
+    let error = client.download(request).await.unwrap_err();
+    assert_eq!(error.error_code(), Some("NotFound"));
+    mock.assert_async().await;
+}
+
+#[derive(Serialize, Debug)]
+#[serde(rename_all = "PascalCase")]
+struct UploadPhoto {
+    album_id: u32,
+    caption: String,
+}
+
+impl ServiceStackRequest for UploadPhoto {
+    type Response = UploadPhotoResponse;
+
+    fn routes() -> &'static [&'static str] {
+        &["/albums/{AlbumId}/photos"]
+    }
+}
+
+#[derive(Deserialize, Debug, PartialEq)]
+struct UploadPhotoResponse {
+    count: u32,
+}
+
+impl ServiceStackResponse for UploadPhotoResponse {}
+
+fn upload_photo() -> UploadPhoto {
+    UploadPhoto {
+        album_id: 7,
+        caption: "Sunset".to_string(),
+    }
+}
+
+#[tokio::test]
+async fn test_post_file_with_request() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/albums/7/photos")
+        .match_header(
+            "content-type",
+            Matcher::Regex("^multipart/form-data; boundary=".to_string()),
+        )
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(r#"name="caption"\r\n\r\nSunset\r\n"#.to_string()),
+            Matcher::Regex(
+                r#"name="photo"; filename="sunset.png"\r\nContent-Type: image/png\r\n\r\nPNG DATA"#
+                    .to_string(),
+            ),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"count":1}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let file = servicestack::FilePart::from_bytes("sunset.png", "PNG DATA").field_name("photo");
+    let response = client
+        .post_file_with_request(upload_photo(), file)
+        .await
+        .unwrap();
+
+    assert_eq!(response, UploadPhotoResponse { count: 1 });
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_post_files_with_request_from_path_and_reader() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/albums/7/photos")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::Regex(
+                r#"name="file"; filename="notes.txt"\r\nContent-Type: text/plain\r\n\r\nfrom disk"#
+                    .to_string(),
+            ),
+            Matcher::Regex(
+                r#"name="file"; filename="data.bin"\r\nContent-Type: application/x-custom\r\n\r\nfrom reader"#
+                    .to_string(),
+            ),
+        ]))
+        .with_status(200)
+        .with_body(r#"{"count":2}"#)
+        .create_async()
+        .await;
+
+    let path = std::env::temp_dir().join(format!("servicestack-upload-{}", std::process::id()));
+    std::fs::create_dir_all(&path).unwrap();
+    let path = path.join("notes.txt");
+    std::fs::write(&path, "from disk").unwrap();
+
+    let client = JsonServiceClient::new(server.url());
+    let files = vec![
+        servicestack::FilePart::from_path(&path),
+        servicestack::FilePart::from_reader("data.bin", &b"from reader"[..])
+            .content_type("application/x-custom")
+            .length(11),
+    ];
+    let response = client
+        .post_files_with_request(upload_photo(), files)
+        .await
+        .unwrap();
+
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(response, UploadPhotoResponse { count: 2 });
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_post_file_with_request_missing_file() {
+    let client = JsonServiceClient::new("http://localhost:1");
+    let file = servicestack::FilePart::from_path("does/not/exist.txt");
+    let error = client
+        .post_file_with_request(upload_photo(), file)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, servicestack::ServiceStackError::IoError(_)));
+}
+
+fn progress_recorder() -> (
+    std::sync::Arc<std::sync::Mutex<Vec<servicestack::Progress>>>,
+    RequestOptions,
+) {
+    let progress = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = progress.clone();
+    let options = RequestOptions::new()
+        .on_upload_progress(move |update| recorded.lock().unwrap().push(update));
+    (progress, options)
+}
+
+#[tokio::test]
+async fn test_send_body_streams_reader_with_query_fields() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/albums/7/photos")
+        .match_query(Matcher::UrlEncoded("caption".into(), "Sunset".into()))
+        .match_header("content-type", "image/png")
+        .match_body("streamed bytes")
+        .with_status(200)
+        .with_body(r#"{"count":1}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let body =
+        servicestack::RequestBody::from_reader(&b"streamed bytes"[..]).content_type("image/png");
+    let response = client.send_body(upload_photo(), body).await.unwrap();
+
+    assert_eq!(response, UploadPhotoResponse { count: 1 });
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_send_body_from_stream_reports_progress() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/albums/7/photos")
+        .match_query(Matcher::UrlEncoded("caption".into(), "Sunset".into()))
+        .match_header("content-length", "10")
+        .match_body("helloworld")
+        .with_status(200)
+        .with_body(r#"{"count":1}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let chunks = futures_util::stream::iter(vec![
+        Ok::<_, std::io::Error>(bytes::Bytes::from("hello")),
+        Ok(bytes::Bytes::from("world")),
+    ]);
+    let body = servicestack::RequestBody::from_stream(chunks).length(10);
+    let (progress, options) = progress_recorder();
+    client
+        .send_body_with(upload_photo(), body, options)
+        .await
+        .unwrap();
+
+    let progress = progress.lock().unwrap().clone();
+    let transferred: Vec<u64> = progress.iter().map(|update| update.transferred).collect();
+    assert_eq!(transferred, vec![5, 10]);
+    assert_eq!(progress.last().unwrap().total, Some(10));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_json_upload_progress() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_body(Matcher::Json(serde_json::json!({"name": "World"})))
+        .with_status(200)
+        .with_body(r#"{"result":"Hello, World!"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let (progress, options) = progress_recorder();
+    client.send_with(request, options).await.unwrap();
+
+    let last = *progress.lock().unwrap().last().unwrap();
+    assert_eq!(last.transferred, r#"{"name":"World"}"#.len() as u64);
+    assert_eq!(last.fraction(), Some(1.0));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_multipart_upload_progress() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/albums/7/photos")
+        .match_body(Matcher::Regex("PNG DATA".to_string()))
+        .with_status(200)
+        .with_body(r#"{"count":1}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let files = vec![servicestack::FilePart::from_bytes("sunset.png", "PNG DATA")];
+    let (progress, options) = progress_recorder();
+    client
+        .post_files_with_request_with(upload_photo(), files, options)
+        .await
+        .unwrap();
+
+    let last = *progress.lock().unwrap().last().unwrap();
+    assert!(last.total.is_some());
+    assert_eq!(Some(last.transferred), last.total);
+    mock.assert_async().await;
+}
+
+fn gunzip(bytes: &[u8]) -> String {
+    use std::io::Read;
+    let mut decompressed = String::new();
+    flate2::read::GzDecoder::new(bytes)
+        .read_to_string(&mut decompressed)
+        .unwrap();
+    decompressed
+}
+
+#[tokio::test]
+async fn test_request_compression_gzips_large_bodies() {
+    let name = "World".repeat(40);
+    let expected = serde_json::json!({ "name": name }).to_string();
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_header("content-encoding", "gzip")
+        .match_request(move |request| gunzip(request.body().unwrap()) == expected)
+        .with_status(200)
+        .with_body(r#"{"result":"Hello"}"#)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .request_compression(
+            servicestack::RequestCompression::new(servicestack::Compression::Gzip).min_size(100),
+        )
+        .build()
+        .unwrap();
+    let request = HelloRequest { name };
+    let response = client.post(request).await.unwrap();
+
+    assert_eq!(response.result, "Hello");
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_request_compression_skips_small_bodies() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_header("content-encoding", Matcher::Missing)
+        .match_body(Matcher::Json(serde_json::json!({"name": "World"})))
+        .with_status(200)
+        .with_body(r#"{"result":"Hello, World!"}"#)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_request_compression(servicestack::RequestCompression::new(
+        servicestack::Compression::Deflate,
+    ));
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    client.post(request).await.unwrap();
+    mock.assert_async().await;
+}
+
+#[cfg(feature = "gzip")]
+#[tokio::test]
+async fn test_gzip_response_is_decompressed() {
+    let compressed = servicestack::Compression::Gzip
+        .compress(br#"{"result":"Hello, World!"}"#)
+        .unwrap();
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("POST", "/hello")
+        .match_header("accept-encoding", Matcher::Regex("gzip".to_string()))
+        .with_status(200)
+        .with_header("content-encoding", "gzip")
+        .with_body(compressed)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::new(server.url());
+    let request = HelloRequest {
+        name: "World".to_string(),
+    };
+    let response = client.post(request).await.unwrap();
+
+    assert_eq!(response.result, "Hello, World!");
+    mock.assert_async().await;
+}
+
+fn get_user() -> GetUserRequest {
+    GetUserRequest {
+        id: 1,
+        include_deleted: false,
+    }
+}
+
+#[tokio::test]
+async fn test_http_cache_reuses_response_on_not_modified() {
+    let mut server = Server::new_async().await;
+    let full = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let not_modified = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(304)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(servicestack::HttpCache::new())
+        .build()
+        .unwrap();
+    for _ in 0..3 {
+        let response = client.get(get_user()).await.unwrap();
+        assert_eq!(response.result, "Alice");
+    }
+
+    let stats = client.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses), (2, 1));
+    full.assert_async().await;
+    not_modified.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_revalidates_with_last_modified() {
+    let last_modified = "Wed, 21 Oct 2026 07:28:00 GMT";
+    let mut server = Server::new_async().await;
+    let full = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-modified-since", Matcher::Missing)
+        .with_status(200)
+        .with_header("last-modified", last_modified)
+        .with_body(r#"{"result":"Alice"}"#)
+        .create_async()
+        .await;
+    let changed = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-modified-since", last_modified)
+        .with_status(200)
+        .with_body(r#"{"result":"Bob"}"#)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::new(server.url());
+    client.set_http_cache(servicestack::HttpCache::new());
+    assert_eq!(client.get(get_user()).await.unwrap().result, "Alice");
+    assert_eq!(client.get(get_user()).await.unwrap().result, "Bob");
+
+    // The changed response has no validator, so the stale one is dropped
+    let cache = client.http_cache().unwrap();
+    assert_eq!(cache.stats().misses, 2);
+    let key = format!("{}/users/1?includeDeleted=false", server.url());
+    assert!(cache.store().get(&key).is_none());
+    full.assert_async().await;
+    changed.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_ignores_no_store_and_post() {
+    let mut server = Server::new_async().await;
+    let get = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_header("cache-control", "no-store")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(2)
+        .create_async()
+        .await;
+    let post = server
+        .mock("POST", "/hello")
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_body(r#"{"result":"Hello"}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let cache = servicestack::HttpCache::new();
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(cache.clone())
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        client.get(get_user()).await.unwrap();
+        let request = HelloRequest {
+            name: "World".to_string(),
+        };
+        client.post(request).await.unwrap();
+    }
+
+    assert_eq!(cache.stats().hits, 0);
+    assert!(cache
+        .store()
+        .get(&format!("{}/users/1?includeDeleted=false", server.url()))
+        .is_none());
+    get.assert_async().await;
+    post.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_sees_headers_added_by_filters() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let cache = servicestack::HttpCache::new();
+    let mut client = JsonServiceClient::builder(server.url())
+        .http_cache(cache.clone())
+        .build()
+        .unwrap();
+    client.add_request_filter(|request: &mut reqwest::Request| {
+        request.headers_mut().insert(
+            "cache-control",
+            reqwest::header::HeaderValue::from_static("no-store"),
+        );
+    });
+    client.get(get_user()).await.unwrap();
+    client.get(get_user()).await.unwrap();
+
+    assert_eq!(cache.stats().hits, 0);
+    mock.assert_async().await;
+}
+
+fn fresh_cache() -> servicestack::HttpCache {
+    servicestack::HttpCache::new().fresh_responses(true)
+}
+
+#[tokio::test]
+async fn test_http_cache_serves_fresh_responses_without_request() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("cache-control", "max-age=60")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(fresh_cache())
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        assert_eq!(client.get(get_user()).await.unwrap().result, "Alice");
+    }
+
+    let stats = client.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses), (1, 1));
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_bypass_and_no_cache_revalidate() {
+    let mut server = Server::new_async().await;
+    let full = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("cache-control", "max-age=60")
+        .with_header("etag", "\"v1\"")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let not_modified = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", "\"v1\"")
+        .match_header("cache-control", "no-cache")
+        .with_status(304)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(fresh_cache())
+        .build()
+        .unwrap();
+    client.get(get_user()).await.unwrap();
+    let options = RequestOptions::new().bypass_cache();
+    let response = client.send_with(get_user(), options).await.unwrap();
+
+    assert_eq!(response.result, "Alice");
+    full.assert_async().await;
+    not_modified.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_revalidates_no_cache_responses() {
+    let mut server = Server::new_async().await;
+    let full = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", Matcher::Missing)
+        .with_status(200)
+        .with_header("cache-control", "no-cache, max-age=60")
+        .with_header("etag", "\"v1\"")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let not_modified = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("if-none-match", "\"v1\"")
+        .with_status(304)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(fresh_cache())
+        .build()
+        .unwrap();
+    client.get(get_user()).await.unwrap();
+    client.get(get_user()).await.unwrap();
+
+    full.assert_async().await;
+    not_modified.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_honors_vary() {
+    let mut server = Server::new_async().await;
+    let english = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("accept-language", "en")
+        .with_status(200)
+        .with_header("cache-control", "max-age=60")
+        .with_header("vary", "Accept-Language")
+        .with_body(r#"{"result":"Hello"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let french = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("accept-language", "fr")
+        .with_status(200)
+        .with_header("cache-control", "max-age=60")
+        .with_header("vary", "Accept-Language")
+        .with_body(r#"{"result":"Bonjour"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(fresh_cache())
+        .build()
+        .unwrap();
+    let language = |value: &str| RequestOptions::new().header("Accept-Language", value);
+    let response = client.send_with(get_user(), language("en")).await.unwrap();
+    assert_eq!(response.result, "Hello");
+    let response = client.send_with(get_user(), language("en")).await.unwrap();
+    assert_eq!(response.result, "Hello");
+    let response = client.send_with(get_user(), language("fr")).await.unwrap();
+    assert_eq!(response.result, "Bonjour");
+
+    english.assert_async().await;
+    french.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_shared_http_cache_skips_private_responses() {
+    let mut server = Server::new_async().await;
+    let mock = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("cache-control", "private, max-age=60")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(2)
+        .create_async()
+        .await;
+
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(fresh_cache().shared(true))
+        .build()
+        .unwrap();
+    client.get(get_user()).await.unwrap();
+    client.get(get_user()).await.unwrap();
+
+    assert_eq!(client.cache_stats().unwrap().hits, 0);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_keeps_fresh_responses_per_bearer_token() {
+    let mut server = Server::new_async().await;
+    let alice = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("authorization", "Bearer alice-token")
+        .with_status(200)
+        .with_header("cache-control", "max-age=60")
+        .with_body(r#"{"result":"Alice"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+    let bob = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("authorization", "Bearer bob-token")
+        .with_status(200)
+        .with_header("cache-control", "max-age=60")
+        .with_body(r#"{"result":"Bob"}"#)
+        .expect(1)
+        .create_async()
+        .await;
+
+    let mut client = JsonServiceClient::builder(server.url())
+        .http_cache(fresh_cache())
+        .build()
+        .unwrap();
+    client.set_bearer_token("alice-token");
+    let bob_client = client.clone();
+
+    assert_eq!(client.get(get_user()).await.unwrap().result, "Alice");
+    let options = RequestOptions::new().bearer_token("bob-token");
+    let response = bob_client.send_with(get_user(), options).await.unwrap();
+    assert_eq!(response.result, "Bob");
+    assert_eq!(client.get(get_user()).await.unwrap().result, "Alice");
+
+    let stats = client.cache_stats().unwrap();
+    assert_eq!((stats.hits, stats.misses), (1, 2));
+    alice.assert_async().await;
+    bob.assert_async().await;
+}
+
+/// Mocks `/users/1` for a tenant, varying on `X-Tenant`
+async fn tenant_mock(server: &mut mockito::ServerGuard, tenant: &str) -> mockito::Mock {
+    server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .match_header("x-tenant", tenant)
+        .with_status(200)
+        .with_header("cache-control", "max-age=60")
+        .with_header("vary", "X-Tenant")
+        .with_body(format!(r#"{{"result":"{}"}}"#, tenant))
+        .expect(1)
+        .create_async()
+        .await
+}
+
+#[tokio::test]
+async fn test_http_cache_varies_on_headers_set_by_filters() {
+    let mut server = Server::new_async().await;
+    let acme = tenant_mock(&mut server, "acme").await;
+    let globex = tenant_mock(&mut server, "globex").await;
+
+    let cache = fresh_cache();
+    let client = |tenant: &'static str| {
+        let mut client = JsonServiceClient::builder(server.url())
+            .http_cache(cache.clone())
+            .build()
+            .unwrap();
+        client.add_request_filter(move |request: &mut reqwest::Request| {
+            request.headers_mut().insert(
+                "x-tenant",
+                reqwest::header::HeaderValue::from_static(tenant),
+            );
+        });
+        client
+    };
+    let (acme_client, globex_client) = (client("acme"), client("globex"));
+
+    assert_eq!(acme_client.get(get_user()).await.unwrap().result, "acme");
+    assert_eq!(
+        globex_client.get(get_user()).await.unwrap().result,
+        "globex"
+    );
+    assert_eq!(
+        globex_client.get(get_user()).await.unwrap().result,
+        "globex"
+    );
+
+    assert_eq!(cache.stats().hits, 1);
+    acme.assert_async().await;
+    globex.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_varies_on_default_headers() {
+    let mut server = Server::new_async().await;
+    let acme = tenant_mock(&mut server, "acme").await;
+    let globex = tenant_mock(&mut server, "globex").await;
+
+    let cache = fresh_cache();
+    let client = |tenant: &str| {
+        JsonServiceClient::builder(server.url())
+            .default_header("X-Tenant", tenant)
+            .http_cache(cache.clone())
+            .build()
+            .unwrap()
+    };
+    let (acme_client, globex_client) = (client("acme"), client("globex"));
+
+    assert_eq!(acme_client.get(get_user()).await.unwrap().result, "acme");
+    assert_eq!(
+        globex_client.get(get_user()).await.unwrap().result,
+        "globex"
+    );
+    assert_eq!(
+        globex_client.get(get_user()).await.unwrap().result,
+        "globex"
+    );
+
+    assert_eq!(cache.stats().hits, 1);
+    acme.assert_async().await;
+    globex.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_http_cache_keys_authorization_default_header() {
+    let mut server = Server::new_async().await;
+    let mut mocks = Vec::new();
+    for user in ["alice", "bob"] {
+        let mock = server
+            .mock("GET", "/users/1")
+            .match_query(Matcher::Any)
+            .match_header("authorization", format!("Bearer {}", user).as_str())
+            .with_status(200)
+            .with_header("cache-control", "max-age=60")
+            .with_body(format!(r#"{{"result":"{}"}}"#, user))
+            .expect(1)
+            .create_async()
+            .await;
+        mocks.push(mock);
+    }
+
+    let cache = fresh_cache();
+    for user in ["alice", "bob"] {
+        let client = JsonServiceClient::builder(server.url())
+            .default_header("Authorization", format!("Bearer {}", user))
+            .http_cache(cache.clone())
+            .build()
+            .unwrap();
+        assert_eq!(client.get(get_user()).await.unwrap().result, user);
+    }
+
+    for mock in mocks {
+        mock.assert_async().await;
+    }
+}
+
+#[tokio::test]
+async fn test_http_cache_passes_through_responses_larger_than_store() {
+    let mut server = Server::new_async().await;
+    let result = "x".repeat(256);
+    let body = format!(r#"{{"result":"{}"}}"#, result);
+    let mock = server
+        .mock("GET", "/users/1")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("etag", "\"v1\"")
+        .with_chunked_body(move |writer| writer.write_all(body.as_bytes()))
+        .expect(2)
+        .create_async()
+        .await;
+
+    let cache = servicestack::HttpCache::with_store(servicestack::MemoryCache::new(64));
+    let client = JsonServiceClient::builder(server.url())
+        .http_cache(cache.clone())
+        .build()
+        .unwrap();
+    for _ in 0..2 {
+        assert_eq!(client.get(get_user()).await.unwrap().result, result);
+    }
+
+    assert_eq!(cache.stats().hits, 0);
+    mock.assert_async().await;
+}
+
+#[tokio::test]
+async fn test_download_bypasses_http_cache_and_streams() {
+    use futures_util::StreamExt;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+    // Sends the second half of the body only after the client has read the first
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    let (first_read, wait_for_read) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut request = [0u8; 1024];
+        let _ = socket.read(&mut request).await.unwrap();
+        socket
+            .write_all(
+                b"HTTP/1.1 200 OK\r\nContent-Length: 10\r\nETag: \"v1\"\r\n\
+                  Cache-Control: max-age=60\r\n\r\nhello",
+            )
+            .await
+            .unwrap();
+        wait_for_read.await.unwrap();
+        socket.write_all(b"world").await.unwrap();
+    });
+
+    let cache = fresh_cache();
+    let client = JsonServiceClient::builder(&url)
+        .http_cache(cache.clone())
+        .build()
+        .unwrap();
+    let request = GetFile {
+        path: "report.bin".to_string(),
+    };
+    let timeout = std::time::Duration::from_secs(5);
+    let download = tokio::time::timeout(timeout, client.download(request))
+        .await
+        .expect("download buffered the body")
+        .unwrap();
+
+    let mut chunks = std::pin::pin!(download.bytes_stream());
+    let first = chunks.next().await.unwrap().unwrap();
+    assert_eq!(&first[..], b"hello");
+    first_read.send(()).unwrap();
+    let mut received = first.to_vec();
+    while let Some(chunk) = chunks.next().await {
+        received.extend_from_slice(&chunk.unwrap());
+    }
+
+    assert_eq!(received, b"helloworld");
+    assert!(cache
+        .store()
+        .get(&format!("{}/files/report.bin", url))
+        .is_none());
+    assert_eq!(cache.stats(), servicestack::CacheStats::default());
+    server.await.unwrap();
+}
+
+#[cfg(feature = "blocking")]
+mod blocking {
+    use super::*;
+    use servicestack::blocking::JsonServiceClient;
+
+    #[test]
+    fn test_blocking_post_and_get() {
+        let mut server = Server::new();
+        let post = server
+            .mock("POST", "/hello")
+            .match_body(Matcher::Json(serde_json::json!({"name": "World"})))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"result":"Hello, World!"}"#)
+            .create();
+        let get = server
+            .mock("GET", "/users/42")
+            .match_query(Matcher::Exact("includeDeleted=true".to_string()))
+            .with_status(200)
+            .with_header("content-type", "application/json")
+            .with_body(r#"{"result":"User 42"}"#)
+            .create();
+
+        let client = JsonServiceClient::new(server.url());
+        let response = client
+            .post(HelloRequest {
+                name: "World".to_string(),
+            })
+            .unwrap();
+        assert_eq!(response.result, "Hello, World!");
+
+        let response = client
+            .get(GetUserRequest {
+                id: 42,
+                include_deleted: true,
+            })
+            .unwrap();
+        assert_eq!(response.result, "User 42");
+        post.assert();
+        get.assert();
+    }
+
+    #[test]
+    fn test_blocking_put_patch_and_delete() {
+        let mut server = Server::new();
+        let put = server
+            .mock("PUT", "/hello")
+            .match_body(Matcher::Json(serde_json::json!({"name": "Put"})))
+            .with_status(200)
+            .with_body(r#"{"result":"Put"}"#)
+            .create();
+        let patch = server
+            .mock("PATCH", "/hello")
+            .match_body(Matcher::Json(serde_json::json!({"name": "Patch"})))
+            .with_status(200)
+            .with_body(r#"{"result":"Patch"}"#)
+            .create();
+        let delete = server
+            .mock("DELETE", "/hello")
+            .match_query(Matcher::UrlEncoded("name".into(), "Delete".into()))
+            .with_status(200)
+            .with_body(r#"{"result":"Delete"}"#)
+            .create();
+
+        let client = JsonServiceClient::new(server.url());
+        for (name, response) in [
+            ("Put", client.put(HelloRequest { name: "Put".into() })),
+            (
+                "Patch",
+                client.patch(HelloRequest {
+                    name: "Patch".into(),
+                }),
+            ),
+            (
+                "Delete",
+                client.delete(HelloRequest {
+                    name: "Delete".into(),
+                }),
+            ),
+        ] {
+            assert_eq!(response.unwrap().result, name);
+        }
+        put.assert();
+        patch.assert();
+        delete.assert();
+    }
+
+    #[test]
+    fn test_blocking_send_uses_request_method() {
+        let mut server = Server::new();
+        let search = server
+            .mock("PUT", "/search")
+            .with_status(200)
+            .with_body(r#"{"results":["result1","result2"]}"#)
+            .create();
+        let delete = server.mock("DELETE", "/users/7").with_status(204).create();
+
+        let client = JsonServiceClient::new(server.url());
+        let response = client
+            .send(SearchRequest {
+                query: "test".to_string(),
+                limit: 10,
+            })
+            .unwrap();
+        assert_eq!(response.results, vec!["result1", "result2"]);
+        client.send(DeleteUser { id: 7 }).unwrap();
+        search.assert();
+        delete.assert();
+    }
+
+    #[test]
+    fn test_blocking_predefined_route_and_bearer_token() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("POST", "/api/ApiHello")
+            .match_header("Authorization", "Bearer test-token-123")
+            .with_status(200)
+            .with_body(r#"{"result":"Authenticated!"}"#)
+            .create();
+
+        #[derive(Serialize)]
+        struct ApiHello {
+            name: String,
+        }
+
+        impl ServiceStackRequest for ApiHello {
+            type Response = HelloResponse;
+        }
+
+        let mut client = JsonServiceClient::new(server.url());
+        client.set_bearer_token("test-token-123");
+        let response = client
+            .post(ApiHello {
+                name: "World".to_string(),
+            })
+            .unwrap();
+        assert_eq!(response.result, "Authenticated!");
+        mock.assert();
+    }
+
+    #[test]
+    fn test_blocking_raw_request() {
+        let mut server = Server::new();
+        let post = server
+            .mock("POST", "/custom")
+            .match_body(Matcher::Json(serde_json::json!({"data": "test"})))
+            .with_status(200)
+            .with_body(r#"{"result":"Custom response"}"#)
+            .create();
+        let get = server
+            .mock("GET", "/custom")
+            .match_query(Matcher::UrlEncoded("data".into(), "test".into()))
+            .with_status(200)
+            .with_body(r#"{"result":"Custom query"}"#)
+            .create();
+
+        let client = JsonServiceClient::new(server.url());
+        let request = serde_json::json!({"data": "test"});
+
+        let response: HelloResponse = client.request("POST", "/custom", Some(&request)).unwrap();
+        assert_eq!(response.result, "Custom response");
+        let response: HelloResponse = client.request("GET", "/custom", Some(&request)).unwrap();
+        assert_eq!(response.result, "Custom query");
+        post.assert();
+        get.assert();
+    }
+
+    #[test]
+    fn test_blocking_debug_redacts_bearer_token() {
+        let mut client = JsonServiceClient::new("https://api.example.com");
+        client.set_bearer_token("secret-token");
+        let debug = format!("{:?}", client);
+        assert!(!debug.contains("secret-token"));
+        assert!(debug.contains("<redacted>"));
+    }
+
+    #[test]
+    fn test_blocking_void_raw_request_ignores_body() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("POST", "/reset")
+            .with_status(200)
+            .with_body("{}")
+            .create();
+
+        let client = JsonServiceClient::new(server.url());
+        client
+            .request::<serde_json::Value, ()>("POST", "/reset", None)
+            .unwrap();
+        mock.assert();
+    }
+
+    #[test]
+    fn test_blocking_error_response() {
+        let mut server = Server::new();
+        let mock = server
+            .mock("POST", "/hello")
+            .with_status(400)
+            .with_header("content-type", "application/json")
+            .with_body(
+                r#"{"responseStatus":{"errorCode":"ArgumentException","message":"Name is required"}}"#,
+            )
+            .create();
+
+        let client = JsonServiceClient::new(server.url());
+        let error = client
+            .post(HelloRequest {
+                name: String::new(),
+            })
+            .unwrap_err();
+        assert_eq!(error.to_string(), "API error: 400 - Name is required");
+        assert_eq!(error.error_code(), Some("ArgumentException"));
+        mock.assert();
+    }
+}